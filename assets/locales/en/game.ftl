@@ -1,3 +1,4 @@
+language-name = English
 main-menu-start-game = Start Game
 main-menu-options = Options
 main-menu-quit-game = Quit Game
@@ -6,4 +7,9 @@ main-menu-random-seed-button = Random Seed
 main-menu-terrain-preview-title = Terrain Preview
 options-title = Options
 options-language-select = Select Language:
-options-back-button = Back
\ No newline at end of file
+options-back-button = Back
+match-moles-remaining = { $count ->
+    [one] { $count } mole remaining
+   *[other] { $count } moles remaining
+}
+match-damage-dealt = { $damage } damage
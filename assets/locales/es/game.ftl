@@ -1,3 +1,4 @@
+language-name = Español
 main-menu-start-game = Iniciar Juego
 main-menu-options = Opciones
 main-menu-quit-game = Salir del Juego
@@ -6,4 +7,9 @@ main-menu-random-seed-button = Semilla Aleatoria
 main-menu-terrain-preview-title = Vista Previa del Terreno
 options-title = Options
 options-language-select = Select Language:
-options-back-button = Back
\ No newline at end of file
+options-back-button = Back
+match-moles-remaining = { $count ->
+    [one] Queda { $count } topo
+   *[other] Quedan { $count } topos
+}
+match-damage-dealt = { $damage } de daño
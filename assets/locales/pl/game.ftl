@@ -1,3 +1,4 @@
+language-name = Polski
 main-menu-start-game = Rozpocznij Grę
 main-menu-options = Opcje
 main-menu-quit-game = Wyjdź z Gry
@@ -6,4 +7,16 @@ main-menu-random-seed-button = Losowe Ziarno
 main-menu-terrain-preview-title = Podgląd Terenu
 options-title = Opcje
 options-language-select = Wybierz Język:
-options-back-button = Powrót
\ No newline at end of file
+options-back-button = Powrót
+match-moles-remaining = { $count ->
+    [one] Został { $count } kret
+    [few] Zostały { $count } krety
+    [many] Zostało { $count } kretów
+   *[other] Zostało { $count } kreta
+}
+match-damage-dealt = { $damage ->
+    [one] { $damage } punkt obrażeń
+    [few] { $damage } punkty obrażeń
+    [many] { $damage } punktów obrażeń
+   *[other] { $damage } punktu obrażeń
+}
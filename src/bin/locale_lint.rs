@@ -0,0 +1,427 @@
+//! Checks every locale directory against the reference language and the locale
+//! manifest, and prints a JSON report. Exits with status 1 when any error is
+//! found, 2 when the locales directory or its manifest cannot be read.
+//!
+//! Usage: `locale-lint [--write-manifest] [LOCALES_DIR]` (defaults to `assets/locales`).
+//!
+//! `--write-manifest` first rewrites the manifest from the language directories,
+//! after a locale has been added or removed.
+
+use fluent_syntax::{ast, parser};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env, fs,
+    path::Path,
+    process::ExitCode,
+};
+use whacka_molee_bevy::localization::discovery::{LocaleManifest, MANIFEST_FILE_NAME};
+use whacka_molee_bevy::localization::{
+    FLUENT_FILE_NAME, LOCALES_PATH, LocaleFileKind, REFERENCE_LANG, line_and_column,
+};
+use whacka_molee_bevy::text_generator::{template_dictionary_keys, template_syntax_error};
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Issue {
+    MissingFile {
+        file: String,
+    },
+    /// A language directory the manifest does not list, so the game never offers it.
+    UnlistedLanguage {
+        directory: String,
+    },
+    /// A locale file the manifest does not list, so the game never loads it.
+    UnlistedFile {
+        file: String,
+    },
+    UnreadableFile {
+        file: String,
+        message: String,
+    },
+    FluentSyntax {
+        file: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    MissingMessage {
+        key: String,
+    },
+    ExtraMessage {
+        key: String,
+    },
+    MissingTemplateCategory {
+        category: String,
+    },
+    TemplateCountMismatch {
+        category: String,
+        count: usize,
+        reference_count: usize,
+    },
+    UnknownDictionaryKey {
+        category: String,
+        template: String,
+        key: String,
+    },
+    InvalidTemplate {
+        category: String,
+        template: String,
+        message: String,
+    },
+}
+
+impl Issue {
+    fn severity(&self) -> Severity {
+        match self {
+            Self::ExtraMessage { .. } | Self::TemplateCountMismatch { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct Finding {
+    severity: Severity,
+    #[serde(flatten)]
+    issue: Issue,
+}
+
+#[derive(Serialize, Debug)]
+struct LocaleReport {
+    lang: String,
+    errors: usize,
+    warnings: usize,
+    findings: Vec<Finding>,
+}
+
+#[derive(Serialize, Debug)]
+struct Report {
+    reference: String,
+    errors: usize,
+    warnings: usize,
+    locales: Vec<LocaleReport>,
+}
+
+/// What could be read from one locale directory.
+#[derive(Default)]
+struct LocaleData {
+    message_ids: Option<BTreeSet<String>>,
+    templates: Option<BTreeMap<String, Vec<String>>>,
+    dictionary_keys: Option<BTreeSet<String>>,
+}
+
+fn main() -> ExitCode {
+    let mut write_manifest = false;
+    let mut locales_dir = LOCALES_PATH.to_string();
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--write-manifest" => write_manifest = true,
+            _ => locales_dir = arg,
+        }
+    }
+    let locales_dir = Path::new(&locales_dir);
+    let manifest_path = locales_dir.join(MANIFEST_FILE_NAME);
+
+    if write_manifest {
+        let written = LocaleManifest::scan(locales_dir).and_then(|manifest| {
+            let json = serde_json::to_string_pretty(&manifest).expect("manifest serializes");
+            fs::write(&manifest_path, json + "\n")
+        });
+        if let Err(e) = written {
+            eprintln!(
+                "locale-lint: cannot write {}: {}",
+                manifest_path.display(),
+                e
+            );
+            return ExitCode::from(2);
+        }
+        eprintln!("locale-lint: wrote {}", manifest_path.display());
+    }
+
+    let mut langs: Vec<String> = match fs::read_dir(locales_dir) {
+        Ok(entries) => entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect(),
+        Err(e) => {
+            eprintln!("locale-lint: cannot read {}: {}", locales_dir.display(), e);
+            return ExitCode::from(2);
+        }
+    };
+    let manifest: LocaleManifest = match fs::read_to_string(&manifest_path)
+        .map_err(|e| e.to_string())
+        .and_then(|source| serde_json::from_str(&source).map_err(|e| e.to_string()))
+    {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            eprintln!(
+                "locale-lint: cannot read {}: {} (--write-manifest creates it)",
+                manifest_path.display(),
+                e
+            );
+            return ExitCode::from(2);
+        }
+    };
+    // Languages listed without a directory are reported as missing every file.
+    for lang in manifest.languages.keys() {
+        if !langs.contains(lang) {
+            langs.push(lang.clone());
+        }
+    }
+    // Reference first, the rest alphabetically.
+    langs.sort_by_key(|lang| (lang != REFERENCE_LANG, lang.clone()));
+    if langs.first().map(String::as_str) != Some(REFERENCE_LANG) {
+        eprintln!(
+            "locale-lint: reference locale '{}' not found in {}",
+            REFERENCE_LANG,
+            locales_dir.display()
+        );
+        return ExitCode::from(2);
+    }
+
+    let mut loaded: Vec<(String, LocaleData, Vec<Issue>)> = langs
+        .into_iter()
+        .map(|lang| {
+            let mut issues = Vec::new();
+            let data = load_locale(&locales_dir.join(&lang), &mut issues);
+            check_manifest(
+                &locales_dir.join(&lang),
+                manifest.languages.get(&lang),
+                &mut issues,
+            );
+            (lang, data, issues)
+        })
+        .collect();
+
+    let (reference_ids, reference_templates) = {
+        let reference = &loaded[0].1;
+        (
+            reference.message_ids.clone().unwrap_or_default(),
+            reference.templates.clone().unwrap_or_default(),
+        )
+    };
+
+    for (lang, data, issues) in &mut loaded {
+        if lang != REFERENCE_LANG {
+            compare_messages(data, &reference_ids, issues);
+            compare_templates(data, &reference_templates, issues);
+        }
+        check_template_keys(data, issues);
+    }
+
+    let locales: Vec<LocaleReport> = loaded
+        .into_iter()
+        .map(|(lang, _, issues)| {
+            let findings: Vec<Finding> = issues
+                .into_iter()
+                .map(|issue| Finding {
+                    severity: issue.severity(),
+                    issue,
+                })
+                .collect();
+            let errors = findings
+                .iter()
+                .filter(|f| f.severity == Severity::Error)
+                .count();
+            LocaleReport {
+                lang,
+                errors,
+                warnings: findings.len() - errors,
+                findings,
+            }
+        })
+        .collect();
+    let report = Report {
+        reference: REFERENCE_LANG.to_string(),
+        errors: locales.iter().map(|locale| locale.errors).sum(),
+        warnings: locales.iter().map(|locale| locale.warnings).sum(),
+        locales,
+    };
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&report).expect("report serializes")
+    );
+    if report.errors > 0 {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn load_locale(lang_dir: &Path, issues: &mut Vec<Issue>) -> LocaleData {
+    let mut data = LocaleData::default();
+
+    if let Some(source) = read_file(lang_dir, FLUENT_FILE_NAME, issues) {
+        let file = lang_dir.join(FLUENT_FILE_NAME).display().to_string();
+        let resource = parser::parse(source.as_str()).unwrap_or_else(|(resource, errors)| {
+            for e in errors {
+                let (line, column) = line_and_column(&source, e.pos.start);
+                issues.push(Issue::FluentSyntax {
+                    file: file.clone(),
+                    line,
+                    column,
+                    message: e.to_string(),
+                });
+            }
+            resource
+        });
+        let ids = resource
+            .body
+            .iter()
+            .filter_map(|entry| match entry {
+                ast::Entry::Message(message) => Some(message.id.name.to_string()),
+                _ => None,
+            })
+            .collect();
+        data.message_ids = Some(ids);
+    }
+
+    if let Some(source) = read_file(lang_dir, "templates.json", issues) {
+        match serde_json::from_str::<BTreeMap<String, serde_json::Value>>(&source) {
+            Ok(raw) => {
+                let templates = raw
+                    .into_iter()
+                    .filter_map(|(category, value)| {
+                        serde_json::from_value::<Vec<String>>(value)
+                            .ok()
+                            .map(|templates| (category, templates))
+                    })
+                    .collect();
+                data.templates = Some(templates);
+            }
+            Err(e) => issues.push(Issue::UnreadableFile {
+                file: lang_dir.join("templates.json").display().to_string(),
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    if let Some(source) = read_file(lang_dir, "dictionaries.json", issues) {
+        match serde_json::from_str::<BTreeMap<String, serde_json::Value>>(&source) {
+            Ok(raw) => data.dictionary_keys = Some(raw.into_keys().collect()),
+            Err(e) => issues.push(Issue::UnreadableFile {
+                file: lang_dir.join("dictionaries.json").display().to_string(),
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    data
+}
+
+fn read_file(lang_dir: &Path, file_name: &str, issues: &mut Vec<Issue>) -> Option<String> {
+    let path = lang_dir.join(file_name);
+    if !path.is_file() {
+        issues.push(Issue::MissingFile {
+            file: path.display().to_string(),
+        });
+        return None;
+    }
+    fs::read_to_string(&path)
+        .map_err(|e| {
+            issues.push(Issue::UnreadableFile {
+                file: path.display().to_string(),
+                message: e.to_string(),
+            })
+        })
+        .ok()
+}
+
+/// Reports a language directory, or locale files in it, that the manifest does not list.
+fn check_manifest(lang_dir: &Path, listed: Option<&Vec<String>>, issues: &mut Vec<Issue>) {
+    let present = LocaleFileKind::ALL
+        .iter()
+        .map(|kind| kind.file_name())
+        .filter(|file| lang_dir.join(file).is_file());
+    let Some(listed) = listed else {
+        if present.count() > 0 {
+            issues.push(Issue::UnlistedLanguage {
+                directory: lang_dir.display().to_string(),
+            });
+        }
+        return;
+    };
+    for file in present {
+        if !listed.iter().any(|listed| listed == file) {
+            issues.push(Issue::UnlistedFile {
+                file: lang_dir.join(file).display().to_string(),
+            });
+        }
+    }
+}
+
+fn compare_messages(data: &LocaleData, reference_ids: &BTreeSet<String>, issues: &mut Vec<Issue>) {
+    let Some(ids) = &data.message_ids else {
+        return;
+    };
+    for key in reference_ids.difference(ids) {
+        issues.push(Issue::MissingMessage { key: key.clone() });
+    }
+    for key in ids.difference(reference_ids) {
+        issues.push(Issue::ExtraMessage { key: key.clone() });
+    }
+}
+
+fn compare_templates(
+    data: &LocaleData,
+    reference_templates: &BTreeMap<String, Vec<String>>,
+    issues: &mut Vec<Issue>,
+) {
+    let Some(templates) = &data.templates else {
+        return;
+    };
+    // The reference language defines which categories exist.
+    for (category, reference) in reference_templates {
+        let reference_count = reference.len();
+        match templates.get(category) {
+            None => issues.push(Issue::MissingTemplateCategory {
+                category: category.clone(),
+            }),
+            Some(list) if list.len() != reference_count => {
+                issues.push(Issue::TemplateCountMismatch {
+                    category: category.clone(),
+                    count: list.len(),
+                    reference_count,
+                })
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+fn check_template_keys(data: &LocaleData, issues: &mut Vec<Issue>) {
+    let (Some(templates), Some(dictionary_keys)) = (&data.templates, &data.dictionary_keys) else {
+        return;
+    };
+    for (category, list) in templates {
+        for template in list {
+            if let Some(message) = template_syntax_error(template) {
+                issues.push(Issue::InvalidTemplate {
+                    category: category.clone(),
+                    template: template.clone(),
+                    message,
+                });
+            }
+            let mut reported = BTreeSet::new();
+            for key in template_dictionary_keys(template) {
+                if !dictionary_keys.contains(&key) && reported.insert(key.clone()) {
+                    issues.push(Issue::UnknownDictionaryKey {
+                        category: category.clone(),
+                        template: template.clone(),
+                        key,
+                    });
+                }
+            }
+        }
+    }
+}
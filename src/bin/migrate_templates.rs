@@ -0,0 +1,100 @@
+//! Rewrites every locale's `templates.json` from the bare placeholder syntax
+//! (`ADJ_TASTE#1 NOUN_VEGETABLE_PLURAL#1`) to the braced one
+//! (`{ADJ_TASTE|agree=vegetable} {NOUN_VEGETABLE|plural|as=vegetable}`).
+//! Only keys of the locale's `dictionaries.json` are rewritten; other words in
+//! capitals are text. Templates already using braces are left as they are, so
+//! running it twice changes nothing. The order of the file is kept. Exits with
+//! status 1 when a file cannot be migrated, 2 when the locales directory itself
+//! cannot be read.
+//!
+//! Usage: `migrate-templates [LOCALES_DIR]` (defaults to `assets/locales`).
+
+use serde_json::Value;
+use std::{collections::BTreeSet, env, fs, path::Path, process::ExitCode};
+use whacka_molee_bevy::localization::LOCALES_PATH;
+use whacka_molee_bevy::text_generator::migrate_legacy_template;
+
+fn main() -> ExitCode {
+    let locales_dir = env::args()
+        .nth(1)
+        .unwrap_or_else(|| LOCALES_PATH.to_string());
+    let locales_dir = Path::new(&locales_dir);
+
+    let mut lang_dirs: Vec<_> = match fs::read_dir(locales_dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect(),
+        Err(e) => {
+            eprintln!(
+                "migrate-templates: cannot read {}: {}",
+                locales_dir.display(),
+                e
+            );
+            return ExitCode::from(2);
+        }
+    };
+    lang_dirs.sort();
+
+    let mut failed = false;
+    for lang_dir in lang_dirs {
+        let path = lang_dir.join("templates.json");
+        if !path.is_file() {
+            continue;
+        }
+        match migrate_file(&path) {
+            Ok(0) => eprintln!("migrate-templates: {} is up to date", path.display()),
+            Ok(changed) => eprintln!(
+                "migrate-templates: {}: {} templates migrated",
+                path.display(),
+                changed
+            ),
+            Err(e) => {
+                eprintln!("migrate-templates: {}: {}", path.display(), e);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Migrates the templates of one file in place, against the dictionary keys of
+/// the same locale; returns how many changed.
+fn migrate_file(path: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+    let dictionaries_path = path.with_file_name("dictionaries.json");
+    let dictionaries = fs::read_to_string(&dictionaries_path)
+        .map_err(|e| format!("{}: {}", dictionaries_path.display(), e))?;
+    let dictionaries: Value = serde_json::from_str(&dictionaries)?;
+    let dictionary_keys: BTreeSet<String> = dictionaries
+        .as_object()
+        .ok_or("expected an object of dictionaries")?
+        .keys()
+        .cloned()
+        .collect();
+    let mut templates: Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let mut changed = 0;
+    for template in templates
+        .as_object_mut()
+        .ok_or("expected an object of template lists")?
+        .values_mut()
+        .filter_map(Value::as_array_mut)
+        .flatten()
+    {
+        if let Some(old) = template.as_str() {
+            let migrated = migrate_legacy_template(old, &dictionary_keys);
+            if migrated != old {
+                *template = Value::String(migrated);
+                changed += 1;
+            }
+        }
+    }
+    if changed > 0 {
+        fs::write(path, serde_json::to_string_pretty(&templates)? + "\n")?;
+    }
+    Ok(changed)
+}
@@ -0,0 +1,283 @@
+//! Prints text generated from the locale files, so edits to `templates.json`
+//! and `dictionaries.json` can be tried without starting the game. Exits with
+//! status 1 when the category is unknown or cannot fit `--max-length`, 2 on
+//! bad arguments or locale files that cannot be loaded.
+//!
+//! Usage: `namegen [--lang LANG|qps] [--category CATEGORY] [--count N] [--seed SEED]
+//! [--max-length N] [--format text|json] [--combinations] [--locales DIR]`
+//!
+//! `--lang qps` generates the pseudo-locale: the reference language's text,
+//! accented as in the game. Validation warnings about the locale files are
+//! printed to stderr.
+//!
+//! Without `--category`, every category of the language is generated. Without
+//! `--seed`, a random seed is used and printed, so a run can be repeated.
+//! `--max-length` generates texts of at most N characters, as name plates do
+//! with the `MAX_LENGTHS` of `templates.json`; a category none of whose texts
+//! fit exits with status 1.
+//! `--combinations` lists roughly how many distinct texts each template can
+//! produce instead of generating any.
+
+use serde::Serialize;
+use std::{env, process::ExitCode};
+use whacka_molee_bevy::localization::{LOCALES_PATH, PSEUDO_LANG, REFERENCE_LANG};
+use whacka_molee_bevy::text_generator::{
+    TextDoesNotFit, TextLimit, WhackaMoleeGenerator, seeded_rng,
+};
+
+const USAGE: &str = "usage: namegen [--lang LANG|qps] [--category CATEGORY] [--count N] [--seed SEED] \
+                     [--max-length N] [--format text|json] [--combinations] [--locales DIR]";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+}
+
+struct Options {
+    lang: String,
+    category: Option<String>,
+    count: usize,
+    seed: Option<u64>,
+    max_length: Option<usize>,
+    format: Format,
+    combinations: bool,
+    locales_dir: String,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options {
+            lang: REFERENCE_LANG.to_string(),
+            category: None,
+            count: 10,
+            seed: None,
+            max_length: None,
+            format: Format::Text,
+            combinations: false,
+            locales_dir: LOCALES_PATH.to_string(),
+        };
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+            match arg.as_str() {
+                "--lang" => options.lang = value()?,
+                "--category" => options.category = Some(value()?.to_uppercase()),
+                "--count" => {
+                    options.count = value()?.parse().map_err(|e| format!("--count: {}", e))?
+                }
+                "--seed" => {
+                    options.seed = Some(value()?.parse().map_err(|e| format!("--seed: {}", e))?)
+                }
+                "--max-length" => {
+                    options.max_length = Some(
+                        value()?
+                            .parse()
+                            .map_err(|e| format!("--max-length: {}", e))?,
+                    )
+                }
+                "--format" => {
+                    options.format = match value()?.as_str() {
+                        "text" => Format::Text,
+                        "json" => Format::Json,
+                        other => return Err(format!("unknown format '{}'", other)),
+                    }
+                }
+                "--combinations" => options.combinations = true,
+                "--locales" => options.locales_dir = value()?,
+                other => return Err(format!("unknown argument '{}'", other)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+#[derive(Serialize)]
+struct Generated<'a> {
+    lang: &'a str,
+    seed: u64,
+    categories: Vec<CategoryTexts<'a>>,
+}
+
+#[derive(Serialize)]
+struct CategoryTexts<'a> {
+    category: &'a str,
+    texts: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Combinations<'a> {
+    lang: &'a str,
+    categories: Vec<CategoryCombinations<'a>>,
+}
+
+#[derive(Serialize)]
+struct CategoryCombinations<'a> {
+    category: &'a str,
+    total: u128,
+    templates: Vec<TemplateCombinations<'a>>,
+}
+
+#[derive(Serialize)]
+struct TemplateCombinations<'a> {
+    template: &'a str,
+    combinations: u128,
+}
+
+fn main() -> ExitCode {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("namegen: {}\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    // The pseudo-locale has no files of its own; it is derived from the reference ones.
+    let is_pseudo = options.lang == PSEUDO_LANG;
+    let source_lang = if is_pseudo {
+        REFERENCE_LANG
+    } else {
+        options.lang.as_str()
+    };
+    let generator = match WhackaMoleeGenerator::new(&options.locales_dir, source_lang) {
+        Ok(generator) if is_pseudo => generator.pseudolocalized(),
+        Ok(generator) => generator,
+        Err(e) => {
+            eprintln!(
+                "namegen: cannot load '{}' from {}: {}",
+                source_lang, options.locales_dir, e
+            );
+            return ExitCode::from(2);
+        }
+    };
+    for warning in generator.validation_report().warnings() {
+        eprintln!("namegen: warning: '{}': {}", source_lang, warning);
+    }
+
+    let categories: Vec<&str> = match &options.category {
+        Some(category) if generator.categories().any(|known| known == category) => {
+            vec![category.as_str()]
+        }
+        Some(category) => {
+            let known: Vec<&str> = generator.categories().collect();
+            eprintln!(
+                "namegen: unknown category '{}'; '{}' has {}",
+                category,
+                options.lang,
+                known.join(", ")
+            );
+            return ExitCode::from(1);
+        }
+        None => generator.categories().collect(),
+    };
+
+    if options.combinations {
+        print_combinations(&generator, &options, &categories);
+    } else if let Err(e) = print_generated(&generator, &options, &categories) {
+        eprintln!(
+            "namegen: {} of {} characters",
+            e,
+            options.max_length.unwrap_or_default()
+        );
+        return ExitCode::from(1);
+    }
+    ExitCode::SUCCESS
+}
+
+fn print_generated(
+    generator: &WhackaMoleeGenerator,
+    options: &Options,
+    categories: &[&str],
+) -> Result<(), TextDoesNotFit> {
+    let seed = options.seed.unwrap_or_else(rand::random);
+    let mut rng = seeded_rng(seed);
+    let generated = Generated {
+        lang: &options.lang,
+        seed,
+        categories: categories
+            .iter()
+            .map(|&category| {
+                let texts = (0..options.count)
+                    .map(|_| match options.max_length {
+                        Some(max) => generator.generate_limited_with_rng(
+                            category,
+                            TextLimit::Chars(max),
+                            &mut rng,
+                        ),
+                        None => Ok(generator.generate_with_rng(category, &mut rng)),
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(CategoryTexts { category, texts })
+            })
+            .collect::<Result<_, _>>()?,
+    };
+
+    match options.format {
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&generated).expect("output serializes")
+        ),
+        Format::Text => {
+            eprintln!("namegen: seed {}", seed);
+            for (i, category) in generated.categories.iter().enumerate() {
+                if categories.len() > 1 {
+                    if i > 0 {
+                        println!();
+                    }
+                    println!("# {}", category.category);
+                }
+                for text in &category.texts {
+                    println!("{}", text);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_combinations(generator: &WhackaMoleeGenerator, options: &Options, categories: &[&str]) {
+    let combinations = Combinations {
+        lang: &options.lang,
+        categories: categories
+            .iter()
+            .map(|&category| {
+                let templates: Vec<TemplateCombinations> = generator
+                    .template_combinations(category)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(template, combinations)| TemplateCombinations {
+                        template,
+                        combinations,
+                    })
+                    .collect();
+                CategoryCombinations {
+                    category,
+                    total: templates
+                        .iter()
+                        .fold(0, |total, t| total.saturating_add(t.combinations)),
+                    templates,
+                }
+            })
+            .collect(),
+    };
+
+    match options.format {
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&combinations).expect("output serializes")
+        ),
+        Format::Text => {
+            for (i, category) in combinations.categories.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                println!(
+                    "# {}: ~{} distinct texts",
+                    category.category, category.total
+                );
+                for template in &category.templates {
+                    println!("{:>12}  {}", template.combinations, template.template);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use bevy::prelude::*;
+use fluent::types::FluentNumber;
+use std::collections::VecDeque;
+
+use crate::game_states::AppState;
+use crate::localization::number_format::NumberFormat;
+use crate::localization::{CurrentLang, TextRole};
+use crate::text_generator::{TextContext, WhackaMoleeGenerator, category};
+
+/// Lines the ticker shows at once; older ones scroll away.
+const TICKER_LINES: usize = 4;
+/// Seconds a line stays in the ticker.
+const TICKER_LINE_SECONDS: f32 = 6.0;
+/// Keys sending a sample [`MatchEvent`] of each kind in debug builds, so the
+/// ticker can be tried before matches report anything.
+const DEBUG_EVENT_KEYS: [KeyCode; 4] = [KeyCode::F5, KeyCode::F6, KeyCode::F7, KeyCode::F8];
+const TICKER_TEXT_COLOR: Color = Color::rgb(1.0, 0.95, 0.8);
+const TICKER_BG_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.45);
+
+/// Something that happened in a match and is worth a line of commentary.
+/// Names are as shown on screen, e.g. generated mole and weapon names.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub enum MatchEvent {
+    MoleDied {
+        victim: String,
+        weapon: String,
+    },
+    DirectHit {
+        attacker: String,
+        victim: String,
+        weapon: String,
+        damage: u32,
+    },
+    FellInWater {
+        victim: String,
+    },
+    TurnTimedOut {
+        team: String,
+    },
+}
+
+impl MatchEvent {
+    /// Text generator category commenting on the event.
+    pub fn category(&self) -> &'static str {
+        match self {
+            Self::MoleDied { .. } => category::COMMENTARY_DEATH,
+            Self::DirectHit { .. } => category::COMMENTARY_DIRECT_HIT,
+            Self::FellInWater { .. } => category::COMMENTARY_DROWNED,
+            Self::TurnTimedOut { .. } => category::COMMENTARY_TURN_TIMEOUT,
+        }
+    }
+
+    /// Values for the `{$NAME}` slots of the category's templates. Numbers
+    /// are written the way `lang` writes them.
+    pub fn context(&self, lang: &str) -> TextContext {
+        match self {
+            Self::MoleDied { victim, weapon } => TextContext::new()
+                .with("victim", victim)
+                .with("weapon", weapon),
+            Self::DirectHit {
+                attacker,
+                victim,
+                weapon,
+                damage,
+            } => TextContext::new()
+                .with("attacker", attacker)
+                .with("victim", victim)
+                .with("weapon", weapon)
+                .with(
+                    "damage",
+                    NumberFormat::for_language(lang).format(&FluentNumber::from(*damage)),
+                ),
+            Self::FellInWater { victim } => TextContext::new().with("victim", victim),
+            Self::TurnTimedOut { team } => TextContext::new().with("team", team),
+        }
+    }
+}
+
+struct TickerLine {
+    text: String,
+    timer: Timer,
+}
+
+/// Commentary lines on screen, oldest first.
+#[derive(Resource, Default)]
+pub struct CommentaryTicker {
+    lines: VecDeque<TickerLine>,
+}
+
+impl CommentaryTicker {
+    pub fn push(&mut self, text: String) {
+        if self.lines.len() == TICKER_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(TickerLine {
+            text,
+            timer: Timer::from_seconds(TICKER_LINE_SECONDS, TimerMode::Once),
+        });
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().map(|line| line.text.as_str())
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+}
+
+/// Turns [`MatchEvent`]s into generated lines shown in an on-screen ticker
+/// while in game. Debug builds send sample events on F5 to F8.
+pub struct CommentaryPlugin;
+
+impl Plugin for CommentaryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<MatchEvent>()
+            .init_resource::<CommentaryTicker>()
+            .add_systems(OnEnter(AppState::InGame), setup_commentary_ticker_ui)
+            .add_systems(
+                Update,
+                (
+                    comment_on_match_events,
+                    expire_ticker_lines,
+                    update_commentary_ticker_ui,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(OnExit(AppState::InGame), cleanup_commentary_ticker_ui);
+        if cfg!(debug_assertions) {
+            app.add_systems(
+                Update,
+                send_debug_match_events
+                    .before(comment_on_match_events)
+                    .run_if(in_state(AppState::InGame)),
+            );
+        }
+    }
+}
+
+#[derive(Component)]
+struct CommentaryTickerUITag;
+
+fn comment_on_match_events(
+    mut events: EventReader<MatchEvent>,
+    generator: Option<Res<WhackaMoleeGenerator>>,
+    current_lang: Res<CurrentLang>,
+    mut ticker: ResMut<CommentaryTicker>,
+) {
+    let Some(generator) = generator else {
+        events.clear();
+        return;
+    };
+    let lang = current_lang.0.to_lowercase();
+    for event in events.read() {
+        ticker.push(generator.generate_with_context(event.category(), &event.context(&lang)));
+    }
+}
+
+/// Sends a [`MatchEvent`] with generated names for each pressed [`DEBUG_EVENT_KEYS`] key.
+fn send_debug_match_events(
+    keys: Res<ButtonInput<KeyCode>>,
+    generator: Option<Res<WhackaMoleeGenerator>>,
+    mut events: EventWriter<MatchEvent>,
+) {
+    let Some(generator) = generator else {
+        return;
+    };
+    let mole = || generator.generate(category::MOLE_UNIT_NAME);
+    let weapon = || generator.generate(category::WEAPON_NAME);
+    for (i, key) in DEBUG_EVENT_KEYS.iter().enumerate() {
+        if !keys.just_pressed(*key) {
+            continue;
+        }
+        events.send(match i {
+            0 => MatchEvent::MoleDied {
+                victim: mole(),
+                weapon: weapon(),
+            },
+            1 => MatchEvent::DirectHit {
+                attacker: mole(),
+                victim: mole(),
+                weapon: weapon(),
+                damage: 25,
+            },
+            2 => MatchEvent::FellInWater { victim: mole() },
+            _ => MatchEvent::TurnTimedOut {
+                team: generator.generate(category::TEAM_NAME),
+            },
+        });
+    }
+}
+
+fn expire_ticker_lines(time: Res<Time>, mut ticker: ResMut<CommentaryTicker>) {
+    // Ticking alone does not change what is on screen.
+    for line in ticker.bypass_change_detection().lines.iter_mut() {
+        line.timer.tick(time.delta());
+    }
+    while ticker
+        .lines
+        .front()
+        .is_some_and(|line| line.timer.finished())
+    {
+        ticker.lines.pop_front();
+    }
+}
+
+fn setup_commentary_ticker_ui(mut commands: Commands, mut ticker: ResMut<CommentaryTicker>) {
+    ticker.clear();
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(20.0),
+                bottom: Val::Px(20.0),
+                max_width: Val::Percent(60.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::FlexStart,
+                ..default()
+            },
+            ..default()
+        },
+        CommentaryTickerUITag,
+    ));
+}
+
+fn update_commentary_ticker_ui(
+    mut commands: Commands,
+    ticker: Res<CommentaryTicker>,
+    container: Query<Entity, With<CommentaryTickerUITag>>,
+) {
+    if !ticker.is_changed() {
+        return;
+    }
+    for entity in &container {
+        commands
+            .entity(entity)
+            .despawn_descendants()
+            .with_children(|parent| {
+                for line in ticker.lines() {
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                margin: UiRect::top(Val::Px(4.0)),
+                                padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                                ..default()
+                            },
+                            background_color: TICKER_BG_COLOR.into(),
+                            ..default()
+                        })
+                        .with_children(|line_parent| {
+                            line_parent.spawn((
+                                TextBundle::from_section(
+                                    line,
+                                    TextStyle {
+                                        font_size: 22.0,
+                                        color: TICKER_TEXT_COLOR,
+                                        ..default()
+                                    },
+                                ),
+                                TextRole::Body,
+                            ));
+                        });
+                }
+            });
+    }
+}
+
+fn cleanup_commentary_ticker_ui(
+    mut commands: Commands,
+    query: Query<Entity, With<CommentaryTickerUITag>>,
+    mut ticker: ResMut<CommentaryTicker>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    ticker.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::localization::{LOCALES_PATH, REFERENCE_LANG};
+
+    #[test]
+    fn match_events_become_ticker_lines() {
+        let locales = format!("{}/{}", env!("CARGO_MANIFEST_DIR"), LOCALES_PATH);
+        let generator = WhackaMoleeGenerator::new(&locales, REFERENCE_LANG).unwrap();
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, CommentaryPlugin))
+            .init_state::<AppState>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<CurrentLang>()
+            .insert_resource(generator);
+        app.world
+            .resource_mut::<NextState<AppState>>()
+            .set(AppState::InGame);
+        app.update();
+
+        app.world.send_event(MatchEvent::FellInWater {
+            victim: "Sir Digsalot".to_string(),
+        });
+        app.update();
+        let lines: Vec<&str> = app.world.resource::<CommentaryTicker>().lines().collect();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("Sir Digsalot"), "{}", lines[0]);
+    }
+}
@@ -0,0 +1,9 @@
+// src/game_objects/mod.rs
+// version:0.0.1
+// ----START OF FILE----
+// player.rs and projectile.rs are still written against macroquad and box2d
+// and are not built until they are ported.
+pub mod terrain;
+// ----END OF FILE----
+// src/game_objects/mod.rs
+// version:0.0.1
@@ -1,76 +1,32 @@
-// src/terrain.rs
-// version:0.1.9
+// src/game_objects/terrain.rs
+// version:0.2.0
 // ----START OF FILE----
-use box2d_rs::b2_body::{B2body, B2bodyDef, B2bodyType};
-use box2d_rs::b2_fixture::B2fixtureDef;
-use box2d_rs::b2_math::B2vec2;
-use box2d_rs::b2_shape::B2shapeDynTrait;
-use box2d_rs::b2_timer::B2timer;
-use box2d_rs::b2_world::B2world;
-use box2d_rs::shapes::b2_polygon_shape::B2polygonShape;
+use bevy::prelude::*;
+use bevy::render::mesh::PrimitiveTopology;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
 use earcut::Earcut;
-use macroquad::prelude::{BLACK as MQ_BLACK, draw_triangle as mq_draw_triangle, vec2 as mq_vec2};
-use macroquad::rand::rand;
-use std::cell::{Ref, RefCell};
-use std::f32::consts::PI;
-use std::rc::Rc;
-use visioncortex::{BinaryImage, BitVec, PathI32, PathSimplifyMode, clusters};
-
 use fastnoise_lite::{FastNoiseLite, FractalType, NoiseType};
+use std::collections::HashSet;
 use std::collections::hash_map::DefaultHasher;
+use std::f32::consts::PI;
 use std::hash::{Hash, Hasher};
-
-use image::{GrayImage, Luma};
-
-use crate::physics::NoUserData;
-type BodyPtr<D> = Rc<RefCell<B2body<D>>>;
-type WorldPtr<D> = Rc<RefCell<B2world<D>>>;
-
-trait FromToGrayImage {
-    fn from_gray_image(img: &GrayImage) -> Self;
-    fn to_gray_image(&self) -> GrayImage;
-    fn new_usize(width: u32, height: u32) -> BinaryImage;
-}
-
-impl FromToGrayImage for BinaryImage {
-    fn from_gray_image(img: &GrayImage) -> Self {
-        // 1) pobieramy wymiary i rzutujemy U32→u32 przez `as` (tu zawsze bezpieczne)
-        let width = img.width() as usize;
-        let height = img.height() as usize;
-
-        // 2) budujemy BitVec, threshold = 0 (piksel > 0  → 1, inaczej 0)
-        let mut bits = BitVec::with_capacity(width * height);
-        for pixel in img.pixels() {
-            let Luma([l]) = *pixel;
-            bits.push(l > 0);
-        }
-
-        BinaryImage {
-            pixels: bits,
-            width,
-            height,
-        }
-    }
-    fn to_gray_image(&self) -> GrayImage {
-        // walidujemy zakres u32 → u32
-        let w32: u32 = self.width.try_into().expect("width exceeds u32::MAX");
-        let h32: u32 = self.height.try_into().expect("height exceeds u32::MAX");
-
-        let mut img = GrayImage::new(w32, h32);
-        for (i, bit) in self.pixels.iter().enumerate() {
-            let x = (i % self.width) as u32;
-            let y = (i / self.width) as u32;
-            let value = if bit { 255 } else { 0 };
-            img.put_pixel(x, y, Luma([value]));
-        }
-        img
-    }
-    fn new_usize(width: u32, height: u32) -> BinaryImage {
-        BinaryImage::new_w_h(width as usize, height as usize)
-    }
-}
-
-const MIN_SPECKLE_SIZE: u32 = 10;
+use std::time::Instant;
+use visioncortex::{BinaryImage, PathI32, PathSimplifyMode, clusters};
+
+use crate::game_states::AppState;
+use crate::text_generator::WhackaMoleeGenerator;
+
+/// Size of the density map in pixels. One pixel is one world unit.
+pub const TERRAIN_WIDTH: u32 = 1280;
+pub const TERRAIN_HEIGHT: u32 = 720;
+/// Side of the square chunks the map is meshed in; each gets its own mesh and collider.
+const CHUNK_SIZE: u32 = 128;
+/// Clusters of solid pixels smaller than this are removed from the map.
+const MIN_SPECKLE_SIZE: usize = 10;
+/// Room a mole needs to stand, in world units, for [`Terrain::is_area_clear`].
+pub const SPAWN_AREA_SIZE: Vec2 = Vec2::new(24.0, 32.0);
+const TERRAIN_COLOR: Color = Color::rgb(0.42, 0.3, 0.2);
 
 #[derive(Debug, Clone, Copy)]
 enum TerrainGenerationType {
@@ -78,170 +34,207 @@ enum TerrainGenerationType {
     SwissCheese,
 }
 
-// Funkcje pomocnicze, które mogą pozostać poza strukturą lub stać się prywatnymi metodami
-// Na razie zostawiam je jako funkcje modułu dla czytelności.
 fn string_to_i32_seed(s: &str) -> i32 {
     let mut hasher = DefaultHasher::new();
     s.hash(&mut hasher);
     hasher.finish() as i32
 }
 
-fn save_density_map_as_png(binary_image: &BinaryImage, filename: &str) {
-    let gray_image = binary_image.to_gray_image();
-
-    match gray_image.save(filename) {
-        Ok(_) => println!("Successfully saved density map to {}", filename),
-        Err(e) => eprintln!("Error saving density map to {}: {}", filename, e),
-    }
-}
-
+/// The destructible ground of a match. `density_map` is the source of truth:
+/// a set pixel is solid, row 0 is the top of the map. Chunks whose pixels
+/// were carved away are meshed again at the end of the frame.
+#[derive(Resource)]
 pub struct Terrain {
     pub density_map: BinaryImage,
-    world: WorldPtr<NoUserData>,
     width: u32,
     height: u32,
-    _seed_str: String,
-    pub active_triangles: Vec<[B2vec2; 3]>,
-    pub body:BodyPtr<NoUserData> 
+    seed: String,
+    /// Origins of the chunks changed since they were last meshed.
+    deformed_chunks: HashSet<UVec2>,
 }
 
 impl Terrain {
-    pub fn new(
-        world: WorldPtr<NoUserData>,
-        width: u32,
-        height: u32,
-        seed_str: &str,
-    ) -> Self {
-        // Zwraca (Terrain, ground_y_level_ref)
-        let seed_i32 = string_to_i32_seed(seed_str);
-        let terrain_choice_seed = seed_i32.wrapping_abs() % 2;
-        let generation_type = if terrain_choice_seed == 0 {
+    /// Terrain of `width` × `height` pixels. The same `seed` gives the same map shape.
+    pub fn new(width: u32, height: u32, seed: &str) -> Self {
+        let seed_i32 = string_to_i32_seed(seed);
+        let generation_type = if seed_i32.wrapping_abs() % 2 == 0 {
             TerrainGenerationType::HillyWithNoise
         } else {
             TerrainGenerationType::SwissCheese
         };
-        println!("Selected terrain type: {:?}", generation_type);
+        info!(
+            "Terrain: generating {:?} terrain from '{}'",
+            generation_type, seed
+        );
 
-        let density_map = match generation_type {
+        let mut density_map = match generation_type {
             TerrainGenerationType::HillyWithNoise => {
-                Self::generate_hilly_terrain_density_map_internal(width, height)
+                Self::generate_hilly_terrain_density_map_internal(width, height, seed_i32)
             }
             TerrainGenerationType::SwissCheese => {
-                Self::generate_swiss_cheese_density_map_internal(width, height)
+                Self::generate_swiss_cheese_density_map_internal(width, height, seed_i32)
             }
         };
+        remove_speckles(&mut density_map, MIN_SPECKLE_SIZE);
 
-        save_density_map_as_png(&density_map, "dbg/terrain.png");
-
-        let triangles = Self::raster_to_triangles(&density_map, 10);
-        let body = Self::build_body(&world, &triangles);
-
-        let terrain_instance = Self {
+        Self {
             density_map,
-            world,
             width,
             height,
-            _seed_str: seed_str.to_string(),
-            active_triangles: triangles,
-            body
-        };
-        (terrain_instance)
+            seed: seed.to_string(),
+            deformed_chunks: HashSet::new(),
+        }
     }
 
-    fn path_to_triangles(path: PathI32, holes: Vec<PathI32>, height: usize) -> Vec<[B2vec2; 3]> {
-        let mut vertices: Vec<[f32; 2]> = Vec::new();
-        let mut hole_indices: Vec<usize> = Vec::new();
-        let triangles_out: &mut Vec<usize> = &mut Vec::new();
-        let mut earcutter = Earcut::new();
-        let mut result_triangles: Vec<[B2vec2; 3]> = Vec::new();
-        for point in path.iter() {
-            vertices.push([point.x as f32, (height - point.y as usize) as f32]);
-        }
-        // for hole in holes {
-        //     for point in hole.path {
-        //         vertices.push([point.x as f32, point.y as f32]);
-        //         hole_indices.push(vertices.len())
-        //     }
-        // }
-        earcutter.earcut(vertices.iter().cloned(), &hole_indices, triangles_out);
-        for k in (0..triangles_out.len()).step_by(3) {
-            let i1 = triangles_out[k];
-            let i2 = triangles_out[k + 1];
-            let i3 = triangles_out[k + 2];
-            let triangle = [
-                B2vec2::new(vertices[i1][0], vertices[i1][1]),
-                B2vec2::new(vertices[i2][0], vertices[i2][1]),
-                B2vec2::new(vertices[i3][0], vertices[i3][1]),
-            ];
-            if triangle[0] != triangle[1]
-                && triangle[1] != triangle[2]
-                && triangle[0] != triangle[2]
-            {
-                result_triangles.push(triangle);
-            } else {
-                eprintln!("Triangle has duplicate points: {:?}", triangle);
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn seed(&self) -> &str {
+        &self.seed
+    }
+
+    /// Where the terrain's bottom left corner is in the world. The map is
+    /// centered on the world origin, where the default 2D camera looks.
+    pub fn world_origin(&self) -> Vec2 {
+        -Vec2::new(self.width as f32, self.height as f32) / 2.0
+    }
+
+    /// The density map position, in pixels, of a point in the world.
+    pub fn world_to_map(&self, world: Vec2) -> Vec2 {
+        let local = world - self.world_origin();
+        Vec2::new(local.x, self.height as f32 - local.y)
+    }
+
+    /// The world position of a density map position, in pixels.
+    pub fn map_to_world(&self, map: Vec2) -> Vec2 {
+        self.world_origin() + Vec2::new(map.x, self.height as f32 - map.y)
+    }
+
+    /// Whether the pixel at `x`, `y` is solid. Pixels outside the map are not.
+    pub fn is_solid(&self, x: i32, y: i32) -> bool {
+        x >= 0
+            && y >= 0
+            && (x as u32) < self.width
+            && (y as u32) < self.height
+            && self.density_map.get_pixel(x as usize, y as usize)
+    }
+
+    /// The chunks covering the map, row by row from the top left.
+    pub fn chunks(&self) -> impl Iterator<Item = TerrainChunk> {
+        let (width, height) = (self.width, self.height);
+        (0..height).step_by(CHUNK_SIZE as usize).flat_map(move |y| {
+            (0..width)
+                .step_by(CHUNK_SIZE as usize)
+                .map(move |x| TerrainChunk {
+                    origin: UVec2::new(x, y),
+                    size: UVec2::new(CHUNK_SIZE.min(width - x), CHUNK_SIZE.min(height - y)),
+                })
+        })
+    }
+
+    /// Triangles covering the solid pixels of `chunk`, relative to its bottom left corner, y up.
+    pub fn chunk_triangles(&self, chunk: &TerrainChunk) -> Vec<[Vec2; 3]> {
+        let mut image = BinaryImage::new_w_h(chunk.size.x as usize, chunk.size.y as usize);
+        for y in 0..chunk.size.y {
+            for x in 0..chunk.size.x {
+                let solid = self
+                    .density_map
+                    .get_pixel((chunk.origin.x + x) as usize, (chunk.origin.y + y) as usize);
+                image.set_pixel(x as usize, y as usize, solid);
             }
+        }
+        Self::raster_to_triangles(&image, 0)
+    }
 
-            // result_triangles.push(triangle);
+    fn path_to_triangles(
+        path: &PathI32,
+        holes: &[PathI32],
+        offset: IVec2,
+        height: usize,
+    ) -> Vec<[Vec2; 3]> {
+        let to_vertex = |x: i32, y: i32| {
+            [
+                (x + offset.x) as f32,
+                (height as i32 - (y + offset.y)) as f32,
+            ]
+        };
+        let mut vertices: Vec<[f32; 2]> = path
+            .iter()
+            .map(|point| to_vertex(point.x, point.y))
+            .collect();
+        let mut hole_indices: Vec<usize> = Vec::new();
+        for hole in holes {
+            hole_indices.push(vertices.len());
+            vertices.extend(hole.iter().map(|point| to_vertex(point.x, point.y)));
         }
-        result_triangles
+
+        let mut triangles_out: Vec<usize> = Vec::new();
+        Earcut::new().earcut(vertices.iter().cloned(), &hole_indices, &mut triangles_out);
+        triangles_out
+            .chunks_exact(3)
+            .filter_map(|indices| {
+                let [a, b, c] = [0, 1, 2].map(|i| Vec2::from(vertices[indices[i]]));
+                // Counter-clockwise, so meshes face the camera and colliders agree on inside.
+                match (b - a).perp_dot(c - a) {
+                    area if area > 0.0 => Some([a, b, c]),
+                    area if area < 0.0 => Some([a, c, b]),
+                    _ => None,
+                }
+            })
+            .collect()
     }
 
-    pub fn raster_to_triangles(
-        binary_img: &BinaryImage,
-        speckle_filter: usize,
-    ) -> Vec<[B2vec2; 3]> {
-        let mut result_triangles: Vec<[B2vec2; 3]> = Vec::new();
-        let timer_total = B2timer::default();
-        let mode = PathSimplifyMode::Polygon;
-        let clusters = binary_img.to_clusters(false);
-        let mut counter = 0;
+    /// Triangulates the solid pixels of `binary_img`, skipping clusters of at
+    /// most `speckle_filter` pixels. Triangles are in pixels with y up, the
+    /// bottom left corner of the image at the origin.
+    pub fn raster_to_triangles(binary_img: &BinaryImage, speckle_filter: usize) -> Vec<[Vec2; 3]> {
+        let started = Instant::now();
         let height = binary_img.height;
+        let mut result_triangles: Vec<[Vec2; 3]> = Vec::new();
 
-        for cluster in clusters {
-            let cluster_triangles: Vec<[B2vec2; 3]>;
-            if cluster.size() > speckle_filter {
-                let image = cluster.to_binary_image();
-                let filename = format!("dbg/terrain{}{}", counter, ".png");
-                counter += 1;
-                save_density_map_as_png(&image, &filename);
-                let paths = clusters::Cluster::image_to_paths(&image, mode);
-                if paths.len() == 1 {
-                    cluster_triangles = Self::path_to_triangles(paths[0].clone(), Vec::new(), height);
-                } else if paths.len() > 1 {
-                    cluster_triangles =
-                    Self::path_to_triangles(paths[0].clone(), paths[1..paths.len()].to_vec(), height);
-                } else {
-                    cluster_triangles = Vec::new();
-                    println!(
-                        "No paths generated for cluster"
-                    );
+        for cluster in binary_img.to_clusters(false) {
+            if cluster.size() <= speckle_filter {
+                continue;
+            }
+            let offset = IVec2::new(cluster.rect.left, cluster.rect.top);
+            let paths = clusters::Cluster::image_to_paths(
+                &cluster.to_binary_image(),
+                PathSimplifyMode::Polygon,
+            );
+            match paths.split_first() {
+                Some((outline, holes)) => {
+                    result_triangles.extend(Self::path_to_triangles(outline, holes, offset, height))
                 }
-
-                println!(
-                    "Cluster {} size {} num paths {}, triangles {}",
-                    counter,
-                    cluster.size(),
-                    paths.len(),
-                    cluster_triangles.len()
-                );
-                result_triangles.extend(cluster_triangles);
+                None => debug!(
+                    "Terrain: no outline for a cluster of {} pixels",
+                    cluster.size()
+                ),
             }
         }
 
-        let total_time_ms = timer_total.get_milliseconds();
-        println!(
-            "Total terrain conversion (image_to_paths_logic + earcut) time {} triangles {}",
-            total_time_ms,
-            result_triangles.len()
+        debug!(
+            "Terrain: {} triangles from {}x{} pixels in {:?}",
+            result_triangles.len(),
+            binary_img.width,
+            binary_img.height,
+            started.elapsed()
         );
         result_triangles
     }
 
-    fn generate_hilly_terrain_density_map_internal(width: u32, height: u32) -> BinaryImage {
+    fn generate_hilly_terrain_density_map_internal(
+        width: u32,
+        height: u32,
+        seed: i32,
+    ) -> BinaryImage {
         let mut binary_image = BinaryImage::new_w_h(width as usize, height as usize);
 
-        let mut noise_generator = FastNoiseLite::with_seed(rand() as i32);
+        let mut noise_generator = FastNoiseLite::with_seed(seed);
         noise_generator.set_noise_type(Some(NoiseType::OpenSimplex2));
         noise_generator.set_frequency(Some(0.005));
 
@@ -270,8 +263,7 @@ impl Terrain {
 
                 let noise_val =
                     noise_generator.get_noise_2d(x_f32 * 0.5, y_f32 * 0.1 + x_f32 * 0.02);
-                let scaled_noise_offset = noise_val * noise_influence_factor;
-                terrain_surface_image_y_sin += scaled_noise_offset;
+                terrain_surface_image_y_sin += noise_val * noise_influence_factor;
 
                 let surface_noise_freq = 0.03;
                 let surface_noise_amp = height as f32 * 0.02;
@@ -280,20 +272,26 @@ impl Terrain {
                 terrain_surface_image_y_sin += surface_noise_val * surface_noise_amp;
                 noise_generator.set_frequency(Some(0.005));
 
-                let terrain_surface_image_y = terrain_surface_image_y_sin
-                    .max(0.0)
-                    .min(height as f32 - 1.0);
-                let is_terrain = y_f32 >= terrain_surface_image_y;
-                binary_image.set_pixel(x_u32 as usize, y_u32 as usize, is_terrain);
+                let terrain_surface_image_y =
+                    terrain_surface_image_y_sin.clamp(0.0, height as f32 - 1.0);
+                binary_image.set_pixel(
+                    x_u32 as usize,
+                    y_u32 as usize,
+                    y_f32 >= terrain_surface_image_y,
+                );
             }
         }
         binary_image
     }
 
-    fn generate_swiss_cheese_density_map_internal(width: u32, height: u32) -> BinaryImage {
+    fn generate_swiss_cheese_density_map_internal(
+        width: u32,
+        height: u32,
+        seed: i32,
+    ) -> BinaryImage {
         let mut binary_image = BinaryImage::new_w_h(width as usize, height as usize);
 
-        let mut hole_noise_gen = FastNoiseLite::with_seed(rand() as i32);
+        let mut hole_noise_gen = FastNoiseLite::with_seed(seed);
         hole_noise_gen.set_noise_type(Some(NoiseType::OpenSimplex2));
         hole_noise_gen.set_frequency(Some(0.002));
         hole_noise_gen.set_fractal_type(Some(FractalType::FBm));
@@ -301,92 +299,396 @@ impl Terrain {
         hole_noise_gen.set_fractal_lacunarity(Some(2.0));
         hole_noise_gen.set_fractal_gain(Some(0.5));
 
-        for y in 0..height as usize {
-            for x in 0..width as usize {
-                binary_image.set_pixel(x, y, true);
-            }
-        }
         let threshold = 0.25;
         for y_u32 in 0..height {
             for x_u32 in 0..width {
-                let x_f32 = x_u32 as f32;
-                let y_f32 = y_u32 as f32;
-                let noise_val = hole_noise_gen.get_noise_2d(x_f32, y_f32);
+                let noise_val = hole_noise_gen.get_noise_2d(x_u32 as f32, y_u32 as f32);
                 let scaled_noise_val = (noise_val + 1.0) * 0.5;
-                if scaled_noise_val > threshold {
-                    binary_image.set_pixel(x_u32 as usize, y_u32 as usize, false);
-                }
+                binary_image.set_pixel(
+                    x_u32 as usize,
+                    y_u32 as usize,
+                    scaled_noise_val <= threshold,
+                );
             }
         }
         binary_image
     }
 
-    fn build_body(
-        world: &WorldPtr<NoUserData>,
-        triangles: &[[B2vec2; 3]],
-    ) -> BodyPtr<NoUserData> {
-        let body_def = B2bodyDef {
-            body_type: B2bodyType::B2StaticBody,
-            position: B2vec2::new(0.0, 0.0),
-            ..Default::default()
-        };
-        let terrain_body = B2world::<NoUserData>::create_body(world.clone(), &body_def);
-        for triangle_vertices in triangles.iter() {
-            let mut polygon_shape = B2polygonShape::default();
-            polygon_shape.set(triangle_vertices);
-            if !polygon_shape.validate() {
-                eprintln!(
-                    "Invalid triangle for B2polygonShape: {:?}",
-                    triangle_vertices
+    /// Carves a crater of radius `size` centered on `x`, `y` of the density
+    /// map, in pixels (see [`Self::world_to_map`]). Returns how many solid
+    /// pixels were removed, so damage and debris can scale with it.
+    pub fn deform_terrain(&mut self, x: f32, y: f32, size: f32) -> usize {
+        if size <= 0.0 {
+            return 0;
+        }
+        let left = (x - size).floor() as i32;
+        let top = (y - size).floor() as i32;
+        let side = (2.0 * size).ceil() as usize + 1;
+        let mut crater = BinaryImage::new_w_h(side, side);
+        for mask_y in 0..side {
+            for mask_x in 0..side {
+                let center = Vec2::new(
+                    (left + mask_x as i32) as f32 + 0.5,
+                    (top + mask_y as i32) as f32 + 0.5,
+                );
+                crater.set_pixel(
+                    mask_x,
+                    mask_y,
+                    center.distance_squared(Vec2::new(x, y)) <= size * size,
                 );
-                continue;
             }
-            let shape_def_ptr: Rc<RefCell<dyn B2shapeDynTrait>> =
-                Rc::new(RefCell::new(polygon_shape));
-            let mut fd = B2fixtureDef::default();
-            fd.shape = Some(shape_def_ptr);
-            B2body::create_fixture(terrain_body.clone(), &fd);
         }
-        let fixture_count = terrain_body.borrow().get_fixture_list().iter().count();
-        println!(
-            "Terrain::build_body_internal, fixture count: {}",
-            fixture_count
+        self.carve(IVec2::new(left, top), &crater)
+    }
+
+    /// Clears every pixel set in `mask`, its top left corner placed on the
+    /// density map pixel `origin`. Parts of the mask off the map are ignored.
+    /// Returns how many solid pixels were removed.
+    pub fn carve(&mut self, origin: IVec2, mask: &BinaryImage) -> usize {
+        let mut removed = 0;
+        for mask_y in 0..mask.height {
+            for mask_x in 0..mask.width {
+                let (x, y) = (origin.x + mask_x as i32, origin.y + mask_y as i32);
+                if !mask.get_pixel(mask_x, mask_y) || !self.is_solid(x, y) {
+                    continue;
+                }
+                self.density_map.set_pixel(x as usize, y as usize, false);
+                self.deformed_chunks
+                    .insert(UVec2::new(x as u32, y as u32) / CHUNK_SIZE * CHUNK_SIZE);
+                removed += 1;
+            }
+        }
+        removed
+    }
+
+    /// Whether a [`SPAWN_AREA_SIZE`] box centered on the world position `pos`
+    /// lies on the map without touching solid ground.
+    pub fn is_area_clear(&self, pos: Vec2) -> bool {
+        self.is_map_area_clear(self.world_to_map(pos).floor().as_ivec2())
+    }
+
+    /// The spot nearest to the world position `pos` where a mole can stand: a
+    /// clear area resting on solid ground. Columns are searched outwards from
+    /// `pos`, each from the top of the map down, so moles land on the surface
+    /// rather than in the caves below it. Returns `pos` when there is no such spot.
+    pub fn find_safe_spawn_location(&self, pos: Vec2) -> Vec2 {
+        let half = (SPAWN_AREA_SIZE / 2.0).as_ivec2();
+        let start_x = self.world_to_map(pos).x.floor() as i32;
+        let step = half.x.max(1);
+        let max_distance = self.width as i32 / step + 1;
+        for distance in 0..=max_distance {
+            let sides: &[i32] = if distance == 0 { &[1] } else { &[1, -1] };
+            for side in sides {
+                let x = start_x + side * distance * step;
+                if x < half.x || x + half.x > self.width as i32 {
+                    continue;
+                }
+                for y in half.y..self.height as i32 - half.y {
+                    let center = IVec2::new(x, y);
+                    if self.is_solid(x, y + half.y) && self.is_map_area_clear(center) {
+                        return self.map_to_world(center.as_vec2());
+                    }
+                }
+            }
+        }
+        warn!("Terrain: no room to spawn near {}", pos);
+        pos
+    }
+
+    fn is_map_area_clear(&self, center: IVec2) -> bool {
+        let half = (SPAWN_AREA_SIZE / 2.0).as_ivec2();
+        let (min, max) = (center - half, center + half - IVec2::ONE);
+        if min.x < 0 || min.y < 0 || max.x >= self.width as i32 || max.y >= self.height as i32 {
+            return false;
+        }
+        (min.y..=max.y).all(|y| (min.x..=max.x).all(|x| !self.is_solid(x, y)))
+    }
+}
+
+/// Clears clusters of fewer than `min_size` solid pixels, so no chunk meshes crumbs.
+fn remove_speckles(density_map: &mut BinaryImage, min_size: usize) {
+    for cluster in density_map.to_clusters(false) {
+        if cluster.size() < min_size {
+            for point in &cluster.points {
+                density_map.set_pixel(point.x as usize, point.y as usize, false);
+            }
+        }
+    }
+}
+
+/// A square of the density map meshed as one entity. `origin` is its top
+/// left pixel.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerrainChunk {
+    pub origin: UVec2,
+    pub size: UVec2,
+}
+
+impl TerrainChunk {
+    /// Position of the chunk's bottom left corner relative to the terrain's, y up.
+    fn translation(&self, terrain_height: u32) -> Vec3 {
+        Vec3::new(
+            self.origin.x as f32,
+            (terrain_height - self.origin.y - self.size.y) as f32,
+            0.0,
+        )
+    }
+}
+
+/// Solid shape of a chunk, as the triangles of its mesh in the chunk's local space.
+/// The app has no physics engine yet, so this is data only: nothing collides with
+/// it on its own, and gameplay code asks it with [`Self::contains_point`] and
+/// [`Self::intersects_circle`].
+#[derive(Component, Debug, Clone)]
+pub struct TerrainCollider {
+    triangles: Vec<[Vec2; 3]>,
+    bounds: Rect,
+}
+
+impl TerrainCollider {
+    pub fn new(triangles: Vec<[Vec2; 3]>) -> Self {
+        let bounds = triangles.iter().flatten().fold(
+            Rect::new(f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+            |bounds, &vertex| bounds.union_point(vertex),
         );
-        terrain_body
+        Self { triangles, bounds }
     }
 
-    pub fn deform_terrain(self, x: f32, y: f32, size: f32) {
-        println!("Terrain::deform_terrain(x={}, y={}, size={})", x, y, size);
+    pub fn triangles(&self) -> &[[Vec2; 3]] {
+        &self.triangles
     }
 
-    pub fn draw(&self) {
-        // pixels_per_unit nie jest już potrzebne jako argument
-        // ... (logika z poprzedniej funkcji draw_terrain) ...
-        // Używa self.active_triangles
-        for triangle in self.active_triangles.iter() {
-            mq_draw_triangle(
-                mq_vec2(triangle[0].x, triangle[0].y),
-                mq_vec2(triangle[1].x, triangle[1].y),
-                mq_vec2(triangle[2].x, triangle[2].y),
-                MQ_BLACK,
-            );
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        self.bounds.contains(point)
+            && self
+                .triangles
+                .iter()
+                .any(|triangle| triangle_contains(triangle, point))
+    }
+
+    /// Whether a circle, e.g. a projectile or a mole, touches the collider.
+    pub fn intersects_circle(&self, center: Vec2, radius: f32) -> bool {
+        let reach = Rect::from_corners(self.bounds.min - radius, self.bounds.max + radius);
+        if !reach.contains(center) {
+            return false;
+        }
+        self.triangles.iter().any(|triangle| {
+            triangle_contains(triangle, center)
+                || (0..3).any(|i| {
+                    distance_to_segment(center, triangle[i], triangle[(i + 1) % 3]) <= radius
+                })
+        })
+    }
+}
+
+/// Whether counter-clockwise `triangle` contains `point`, edges included.
+fn triangle_contains(triangle: &[Vec2; 3], point: Vec2) -> bool {
+    (0..3).all(|i| {
+        let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+        (b - a).perp_dot(point - a) >= 0.0
+    })
+}
+
+fn distance_to_segment(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let along = (point - a).dot(b - a) / (b - a).length_squared();
+    point.distance(a + (b - a) * along.clamp(0.0, 1.0))
+}
+
+fn triangles_to_mesh(triangles: &[[Vec2; 3]]) -> Mesh {
+    let positions: Vec<[f32; 3]> = triangles
+        .iter()
+        .flatten()
+        .map(|vertex| [vertex.x, vertex.y, 0.0])
+        .collect();
+    let normals = vec![[0.0, 0.0, 1.0]; positions.len()];
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+}
+
+/// Generates the terrain when a match starts, named by the text generator,
+/// and removes it when the match ends.
+pub struct TerrainPlugin;
+
+impl Plugin for TerrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::InGame), spawn_terrain)
+            .add_systems(
+                PostUpdate,
+                rebuild_deformed_chunks
+                    .run_if(in_state(AppState::InGame).and_then(resource_exists::<Terrain>)),
+            )
+            .add_systems(OnExit(AppState::InGame), despawn_terrain);
+    }
+}
+
+#[derive(Component)]
+struct TerrainTag;
+
+fn spawn_terrain(
+    mut commands: Commands,
+    generator: Option<Res<WhackaMoleeGenerator>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let seed = match generator {
+        Some(generator) => generator.generate_terrain_name(),
+        None => rand::random::<u64>().to_string(),
+    };
+    let terrain = Terrain::new(TERRAIN_WIDTH, TERRAIN_HEIGHT, &seed);
+    let material = materials.add(ColorMaterial::from(TERRAIN_COLOR));
+
+    commands
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_translation(
+                terrain.world_origin().extend(0.0),
+            )),
+            TerrainTag,
+        ))
+        .with_children(|parent| {
+            for chunk in terrain.chunks() {
+                let triangles = terrain.chunk_triangles(&chunk);
+                if triangles.is_empty() {
+                    continue;
+                }
+                parent.spawn((
+                    MaterialMesh2dBundle {
+                        mesh: meshes.add(triangles_to_mesh(&triangles)).into(),
+                        material: material.clone(),
+                        transform: Transform::from_translation(chunk.translation(terrain.height())),
+                        ..default()
+                    },
+                    TerrainCollider::new(triangles),
+                    chunk,
+                ));
+            }
+        });
+    commands.insert_resource(terrain);
+}
+
+/// Meshes the chunks carved this frame again. Chunks carved away entirely
+/// are despawned.
+fn rebuild_deformed_chunks(
+    mut commands: Commands,
+    mut terrain: ResMut<Terrain>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut chunks: Query<(Entity, &TerrainChunk, &Mesh2dHandle, &mut TerrainCollider)>,
+) {
+    if terrain.deformed_chunks.is_empty() {
+        return;
+    }
+    let deformed = std::mem::take(&mut terrain.deformed_chunks);
+    for (entity, chunk, mesh, mut collider) in chunks.iter_mut() {
+        if !deformed.contains(&chunk.origin) {
+            continue;
+        }
+        let triangles = terrain.chunk_triangles(chunk);
+        if triangles.is_empty() {
+            commands.entity(entity).despawn_recursive();
+            continue;
         }
+        if let Some(mesh) = meshes.get_mut(&mesh.0) {
+            *mesh = triangles_to_mesh(&triangles);
+        }
+        *collider = TerrainCollider::new(triangles);
     }
+}
 
-    // Helper function to check if a rectangular area in the density map is clear
-    pub fn is_area_clear(&mut self, pos: B2vec2) -> bool {
-        println!("Terrain::is_area_clear(pos=({},{})", pos.x, pos.y);
-        true
+fn despawn_terrain(mut commands: Commands, query: Query<Entity, With<TerrainTag>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<Terrain>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collider_covers_its_triangles() {
+        let collider = TerrainCollider::new(vec![[
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(0.0, 10.0),
+        ]]);
+        assert!(collider.contains_point(Vec2::new(2.0, 2.0)));
+        assert!(!collider.contains_point(Vec2::new(8.0, 8.0)));
+        assert!(collider.intersects_circle(Vec2::new(8.0, 8.0), 5.0));
+        assert!(!collider.intersects_circle(Vec2::new(8.0, 8.0), 2.0));
+        assert!(!collider.intersects_circle(Vec2::new(-5.0, 5.0), 4.0));
+    }
+
+    #[test]
+    fn chunks_cover_the_map() {
+        let terrain = Terrain::new(300, 200, "Pickle Plains");
+        let chunks: Vec<TerrainChunk> = terrain.chunks().collect();
+        assert_eq!(chunks.len(), 3 * 2);
+        let area: u32 = chunks.iter().map(|chunk| chunk.size.x * chunk.size.y).sum();
+        assert_eq!(area, 300 * 200);
+        assert_eq!(chunks[5].translation(200), Vec3::new(256.0, 0.0, 0.0));
     }
 
-    pub fn find_safe_spawn_location(&mut self, pos: B2vec2) -> B2vec2 {
-        println!(
-            "Terrain::find_safe_spawn_location(pos=({},{})",
-            pos.x, pos.y
+    #[test]
+    fn craters_remove_solid_pixels_once() {
+        let mut terrain = Terrain::new(300, 200, "Pickle Plains");
+        for y in 0..200 {
+            for x in 0..300 {
+                terrain.density_map.set_pixel(x, y, true);
+            }
+        }
+
+        let removed = terrain.deform_terrain(128.0, 100.0, 10.0);
+        assert!((300..=330).contains(&removed), "{} pixels", removed);
+        assert!(
+            !terrain.is_solid(128, 100)
+                && !terrain.is_solid(120, 100)
+                && terrain.is_solid(140, 100)
         );
-        pos
+        assert_eq!(
+            terrain.deformed_chunks,
+            HashSet::from([UVec2::new(0, 0), UVec2::new(128, 0)])
+        );
+        assert_eq!(terrain.deform_terrain(128.0, 100.0, 10.0), 0);
+
+        let mut mask = BinaryImage::new_w_h(4, 4);
+        mask.set_pixel(0, 0, true);
+        mask.set_pixel(3, 3, true);
+        assert_eq!(terrain.carve(IVec2::new(-3, -3), &mask), 1);
+        assert!(!terrain.is_solid(0, 0) && terrain.is_solid(1, 1));
+    }
+
+    #[test]
+    fn moles_spawn_on_the_surface() {
+        let mut terrain = Terrain::new(300, 200, "Pickle Plains");
+        for y in 0..200 {
+            for x in 0..300 {
+                terrain.density_map.set_pixel(x, y, y >= 150);
+            }
+        }
+        let above_ground = terrain.map_to_world(Vec2::new(100.0, 40.0));
+        assert!(terrain.is_area_clear(above_ground));
+        assert!(!terrain.is_area_clear(terrain.map_to_world(Vec2::new(100.0, 160.0))));
+
+        let spawn = terrain.find_safe_spawn_location(above_ground);
+        assert_eq!(
+            terrain.world_to_map(spawn),
+            Vec2::new(100.0, 150.0 - SPAWN_AREA_SIZE.y / 2.0)
+        );
+        assert!(terrain.is_area_clear(spawn));
+
+        // A mole asking for a spot under the ground is moved up onto it.
+        let buried = terrain.map_to_world(Vec2::new(100.0, 180.0));
+        assert_eq!(terrain.find_safe_spawn_location(buried), spawn);
+
+        terrain.density_map = BinaryImage::new_w_h(300, 200);
+        let nowhere = terrain.map_to_world(Vec2::new(100.0, 40.0));
+        assert_eq!(terrain.find_safe_spawn_location(nowhere), nowhere);
     }
 }
 // ----END OF FILE----
-// src/terrain.rs
-// version:0.1.9
+// src/game_objects/terrain.rs
+// version:0.2.0
@@ -0,0 +1,7 @@
+pub mod commentary;
+pub mod game_objects;
+pub mod game_states;
+pub mod localization;
+pub mod settings;
+pub mod text_generator;
+pub mod ui;
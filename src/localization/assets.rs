@@ -0,0 +1,218 @@
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext, LoadState, io::Reader};
+use bevy::prelude::*;
+use bevy::utils::BoxedFuture;
+use std::collections::HashMap;
+
+use super::discovery::{LocaleManifest, MANIFEST_FILE_NAME};
+use super::{AvailableLanguages, CurrentLang, FLUENT_FILE_NAME, pick_starting_language};
+use crate::settings::UserSettings;
+
+/// Locales directory as seen by the asset server, relative to its asset root.
+pub const LOCALES_ASSET_PATH: &str = "locales";
+
+/// The files every language directory may contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LocaleFileKind {
+    Fluent,
+    Templates,
+    Dictionaries,
+}
+
+impl LocaleFileKind {
+    pub const ALL: [LocaleFileKind; 3] = [Self::Fluent, Self::Templates, Self::Dictionaries];
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Fluent => FLUENT_FILE_NAME,
+            Self::Templates => "templates.json",
+            Self::Dictionaries => "dictionaries.json",
+        }
+    }
+}
+
+/// Raw text of one `game.ftl`, reloaded by the asset server when it changes on disk.
+#[derive(Asset, TypePath, Debug)]
+pub struct LocaleFile {
+    pub text: String,
+}
+
+#[derive(Default)]
+pub struct LocaleFileLoader;
+
+impl AssetLoader for LocaleFileLoader {
+    type Asset = LocaleFile;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(LocaleFile {
+                text: String::from_utf8(bytes)?,
+            })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ftl"]
+    }
+}
+
+#[derive(Default)]
+pub struct LocaleManifestLoader;
+
+impl AssetLoader for LocaleManifestLoader {
+    type Asset = LocaleManifest;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(serde_json::from_slice(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["manifest.json"]
+    }
+}
+
+/// The locale manifest and the `game.ftl` of every available language.
+/// The dictionaries and templates are loaded by the text generator.
+#[derive(Resource, Default)]
+pub struct LocaleFiles {
+    manifest: Handle<LocaleManifest>,
+    /// The manifest has been read, or failed to load.
+    discovered: bool,
+    handles: HashMap<String, Handle<LocaleFile>>,
+}
+
+impl LocaleFiles {
+    pub fn handle(&self, lang: &str) -> Option<&Handle<LocaleFile>> {
+        self.handles.get(lang)
+    }
+
+    /// Text of a loaded `game.ftl`.
+    pub fn text<'a>(&self, lang: &str, assets: &'a Assets<LocaleFile>) -> Option<&'a str> {
+        let handle = self.handle(lang)?;
+        assets.get(handle).map(|file| file.text.as_str())
+    }
+
+    /// Language of the file behind an asset id.
+    pub fn identify(&self, id: AssetId<LocaleFile>) -> Option<&str> {
+        self.handles
+            .iter()
+            .find(|(_, handle)| handle.id() == id)
+            .map(|(lang, _)| lang.as_str())
+    }
+
+    /// Whether the languages are known and every requested file has either loaded or failed.
+    pub fn all_settled(&self, asset_server: &AssetServer) -> bool {
+        self.discovered
+            && self.handles.values().all(|handle| {
+                matches!(
+                    asset_server.load_state(handle),
+                    LoadState::Loaded | LoadState::Failed
+                )
+            })
+    }
+
+    /// Requests the `game.ftl` of every language that has one.
+    fn load_fluent_files(&mut self, available: &AvailableLanguages, asset_server: &AssetServer) {
+        for language in available.iter().filter(|language| language.has_fluent) {
+            let path = format!(
+                "{}/{}/{}",
+                LOCALES_ASSET_PATH, language.code, FLUENT_FILE_NAME
+            );
+            self.handles
+                .insert(language.code.clone(), asset_server.load(path));
+        }
+    }
+}
+
+/// Languages whose `game.ftl` finished loading or changed on disk this frame.
+pub fn changed_locale_files<'a>(
+    events: &mut EventReader<AssetEvent<LocaleFile>>,
+    locale_files: &'a LocaleFiles,
+) -> Vec<&'a str> {
+    let mut changed = Vec::new();
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event
+        else {
+            continue;
+        };
+        if let Some(lang) = locale_files.identify(*id)
+            && !changed.contains(&lang)
+        {
+            changed.push(lang);
+        }
+    }
+    changed
+}
+
+pub(super) fn load_locale_manifest(
+    asset_server: Res<AssetServer>,
+    mut locale_files: ResMut<LocaleFiles>,
+) {
+    let path = format!("{}/{}", LOCALES_ASSET_PATH, MANIFEST_FILE_NAME);
+    locale_files.manifest = asset_server.load(path);
+}
+
+/// Rebuilds [`AvailableLanguages`] whenever the manifest loads or changes on
+/// disk, and requests the `game.ftl` of every language in it. The starting
+/// language is picked once the languages are first known.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn discover_languages(
+    mut events: EventReader<AssetEvent<LocaleManifest>>,
+    manifests: Res<Assets<LocaleManifest>>,
+    locale_assets: Res<Assets<LocaleFile>>,
+    asset_server: Res<AssetServer>,
+    settings: Res<UserSettings>,
+    mut locale_files: ResMut<LocaleFiles>,
+    mut available: ResMut<AvailableLanguages>,
+    mut current_lang: ResMut<CurrentLang>,
+) {
+    let manifest_id = locale_files.manifest.id();
+    let mut manifest_changed = false;
+    for event in events.read() {
+        if let AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } = event {
+            manifest_changed |= *id == manifest_id;
+        }
+    }
+    let first = !locale_files.discovered;
+    if manifest_changed && let Some(manifest) = manifests.get(manifest_id) {
+        *available = AvailableLanguages::from_manifest(manifest);
+        locale_files.load_fluent_files(&available, &asset_server);
+        // Files loaded before a reload will not load again, so their messages are recorded now.
+        let codes: Vec<String> = available
+            .iter()
+            .map(|language| language.code.clone())
+            .collect();
+        for code in codes {
+            if let Some(source) = locale_files.text(&code, &locale_assets) {
+                available.record_messages(&code, source);
+            }
+        }
+        locale_files.discovered = true;
+    } else if first && asset_server.load_state(manifest_id) == LoadState::Failed {
+        // The asset server has logged why; the game runs with untranslated keys.
+        error!("Localization: no locale manifest, no languages available");
+        locale_files.discovered = true;
+    }
+    if first && locale_files.discovered {
+        pick_starting_language(&available, &settings, &mut current_lang);
+    }
+}
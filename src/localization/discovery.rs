@@ -0,0 +1,329 @@
+use bevy::prelude::*;
+use fluent::FluentResource;
+use fluent_syntax::ast;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    env, fs, io,
+    path::Path,
+};
+use unic_langid::LanguageIdentifier;
+
+use super::REFERENCE_LANG;
+use super::assets::LocaleFileKind;
+use super::pseudo::{PSEUDO_DISPLAY_NAME, PSEUDO_LANG};
+
+/// Message every `game.ftl` defines with the language's name in that language.
+pub const LANGUAGE_NAME_KEY: &str = "language-name";
+/// Lists the language directories under the locales path, since the asset
+/// server cannot list directories on every platform. Written by
+/// `locale-lint --write-manifest`, which also fails when it is out of date.
+pub const MANIFEST_FILE_NAME: &str = "locales.manifest.json";
+
+/// Contents of [`MANIFEST_FILE_NAME`]: the files of each language directory, by code.
+#[derive(Asset, TypePath, Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct LocaleManifest {
+    pub languages: BTreeMap<String, Vec<String>>,
+}
+
+impl LocaleManifest {
+    /// Lists the language directories of `base_path` and the locale files in
+    /// them, as the manifest should. Directories whose name is not a language
+    /// identifier, or that hold no locale files, are left out. For tools; the
+    /// game reads the manifest through the asset server.
+    pub fn scan(base_path: &Path) -> io::Result<Self> {
+        let mut languages = BTreeMap::new();
+        for entry in fs::read_dir(base_path)?.flatten() {
+            let path = entry.path();
+            let Some(code) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if !path.is_dir() || code.parse::<LanguageIdentifier>().is_err() {
+                continue;
+            }
+            let files: Vec<String> = LocaleFileKind::ALL
+                .iter()
+                .map(|kind| kind.file_name())
+                .filter(|file| path.join(file).is_file())
+                .map(str::to_string)
+                .collect();
+            if !files.is_empty() {
+                languages.insert(code.to_string(), files);
+            }
+        }
+        Ok(Self { languages })
+    }
+}
+
+/// A language listed in the locale manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageInfo {
+    pub code: String,
+    /// Native name from [`LANGUAGE_NAME_KEY`], or the code until `game.ftl` has loaded.
+    pub display_name: String,
+    pub has_fluent: bool,
+    pub has_templates: bool,
+    pub has_dictionaries: bool,
+    /// Every message of the reference language is also defined here.
+    pub messages_complete: bool,
+}
+
+impl LanguageInfo {
+    pub fn is_complete(&self) -> bool {
+        // The pseudo-locale has no files; it is built from the reference language in memory.
+        let files_complete = self.code == PSEUDO_LANG
+            || (self.has_fluent && self.has_templates && self.has_dictionaries);
+        files_complete && self.messages_complete
+    }
+
+    /// Whether the language directory has a file of `kind`.
+    pub fn has_file(&self, kind: LocaleFileKind) -> bool {
+        match kind {
+            LocaleFileKind::Fluent => self.has_fluent,
+            LocaleFileKind::Templates => self.has_templates,
+            LocaleFileKind::Dictionaries => self.has_dictionaries,
+        }
+    }
+}
+
+/// Languages listed in the locale manifest, sorted by code.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct AvailableLanguages {
+    languages: Vec<LanguageInfo>,
+    /// Message ids of every `game.ftl` recorded so far, by language.
+    message_ids: HashMap<String, BTreeSet<String>>,
+}
+
+impl AvailableLanguages {
+    /// Languages of `manifest`. Codes that are not valid language identifiers,
+    /// and languages listing no locale files, are skipped. Debug builds also
+    /// list the pseudo-locale when the reference language is present.
+    pub fn from_manifest(manifest: &LocaleManifest) -> Self {
+        let mut languages = Vec::new();
+        for (code, files) in &manifest.languages {
+            if code.parse::<LanguageIdentifier>().is_err() {
+                warn!(
+                    "Localization: skipping '{}', not a language identifier",
+                    code
+                );
+                continue;
+            }
+            let listed = |kind: LocaleFileKind| files.iter().any(|file| file == kind.file_name());
+            for file in files {
+                if !LocaleFileKind::ALL
+                    .iter()
+                    .any(|kind| kind.file_name() == file)
+                {
+                    warn!(
+                        "Localization: ignoring unknown locale file '{}/{}'",
+                        code, file
+                    );
+                }
+            }
+            let language = LanguageInfo {
+                code: code.clone(),
+                display_name: code.clone(),
+                has_fluent: listed(LocaleFileKind::Fluent),
+                has_templates: listed(LocaleFileKind::Templates),
+                has_dictionaries: listed(LocaleFileKind::Dictionaries),
+                messages_complete: false,
+            };
+            if LocaleFileKind::ALL
+                .iter()
+                .any(|kind| language.has_file(*kind))
+            {
+                languages.push(language);
+            }
+        }
+
+        // The pseudo-locale is a testing aid, so release builds do not offer it.
+        if cfg!(debug_assertions)
+            && languages
+                .iter()
+                .any(|language| language.code == REFERENCE_LANG)
+        {
+            // Built from the reference language in memory, so there are no files to load.
+            languages.push(LanguageInfo {
+                code: PSEUDO_LANG.to_string(),
+                display_name: PSEUDO_DISPLAY_NAME.to_string(),
+                has_fluent: false,
+                has_templates: false,
+                has_dictionaries: false,
+                messages_complete: false,
+            });
+        }
+        languages.sort_by(|a, b| a.code.cmp(&b.code));
+
+        for language in &languages {
+            info!("Localization: found '{}'", language.code);
+        }
+        Self {
+            languages,
+            message_ids: HashMap::new(),
+        }
+    }
+
+    /// Takes the display name and message ids of `lang` from its `game.ftl`,
+    /// and rechecks which languages define every reference message.
+    pub fn record_messages(&mut self, lang: &str, source: &str) {
+        let messages = LocaleMessages::parse(source);
+        if let Some(language) = self
+            .languages
+            .iter_mut()
+            .find(|language| language.code == lang)
+            && let Some(display_name) = messages.display_name
+        {
+            language.display_name = display_name;
+        }
+        self.message_ids.insert(lang.to_string(), messages.ids);
+
+        let reference_ids = self.message_ids.get(REFERENCE_LANG);
+        for language in &mut self.languages {
+            let messages_lang = if language.code == PSEUDO_LANG {
+                REFERENCE_LANG
+            } else {
+                &language.code
+            };
+            let complete = match (reference_ids, self.message_ids.get(messages_lang)) {
+                (Some(reference_ids), Some(ids)) => reference_ids.is_subset(ids),
+                _ => false,
+            };
+            if lang == language.code && !complete && reference_ids.is_some() {
+                info!(
+                    "Localization: '{}' ({}) is incomplete",
+                    language.code, language.display_name
+                );
+            }
+            language.messages_complete = complete;
+        }
+    }
+
+    pub fn get(&self, code: &str) -> Option<&LanguageInfo> {
+        self.languages.iter().find(|language| language.code == code)
+    }
+
+    pub fn contains(&self, code: &str) -> bool {
+        self.get(code).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &LanguageInfo> {
+        self.languages.iter()
+    }
+
+    /// Best available language for a POSIX locale such as `pl_PL.UTF-8`:
+    /// an exact match of the identifier first, then one on the language subtag alone.
+    pub fn match_locale(&self, locale: &str) -> Option<&LanguageInfo> {
+        let name = locale.split(['.', '@']).next().unwrap_or_default();
+        if name.is_empty() || name == "C" || name == "POSIX" {
+            return None;
+        }
+        let langid: LanguageIdentifier = name.replace('_', "-").parse().ok()?;
+        self.get(&langid.to_string())
+            .or_else(|| self.get(langid.language.as_str()))
+    }
+
+    /// Language matching the user's locale environment, honoring the POSIX
+    /// precedence `LC_ALL` > `LC_MESSAGES` > `LANG`.
+    pub fn detect_system_language(&self) -> Option<&LanguageInfo> {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty())?;
+        let language = self.match_locale(&locale);
+        if language.is_none() {
+            info!(
+                "Localization: no locale available for system locale '{}'",
+                locale
+            );
+        }
+        language
+    }
+}
+
+/// Message ids and display name of one `game.ftl`.
+struct LocaleMessages {
+    ids: BTreeSet<String>,
+    display_name: Option<String>,
+}
+
+impl LocaleMessages {
+    fn parse(source: &str) -> Self {
+        // Syntax errors are reported when the bundle is built; recovered entries are enough here.
+        let resource =
+            FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, _)| resource);
+
+        let mut ids = BTreeSet::new();
+        let mut display_name = None;
+        for entry in resource.entries() {
+            let ast::Entry::Message(message) = entry else {
+                continue;
+            };
+            ids.insert(message.id.name.to_string());
+            if message.id.name == LANGUAGE_NAME_KEY {
+                display_name = message.value.as_ref().map(plain_text);
+            }
+        }
+        Self { ids, display_name }
+    }
+}
+
+/// Concatenates the text elements of a pattern, ignoring placeables.
+fn plain_text(pattern: &ast::Pattern<&str>) -> String {
+    pattern
+        .elements
+        .iter()
+        .filter_map(|element| match element {
+            ast::PatternElement::TextElement { value } => Some(*value),
+            ast::PatternElement::Placeable { .. } => None,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::localization::{FLUENT_FILE_NAME, LOCALES_PATH};
+
+    #[test]
+    fn pseudo_locale_asks_for_no_files() {
+        let locales = Path::new(env!("CARGO_MANIFEST_DIR")).join(LOCALES_PATH);
+        let manifest = fs::read_to_string(locales.join(MANIFEST_FILE_NAME)).unwrap();
+        let mut available =
+            AvailableLanguages::from_manifest(&serde_json::from_str(&manifest).unwrap());
+        let reference_source =
+            fs::read_to_string(locales.join(REFERENCE_LANG).join(FLUENT_FILE_NAME)).unwrap();
+        available.record_messages(REFERENCE_LANG, &reference_source);
+        assert!(
+            available
+                .get(REFERENCE_LANG)
+                .is_some_and(LanguageInfo::is_complete)
+        );
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        let pseudo = available
+            .get(PSEUDO_LANG)
+            .expect("debug builds list the pseudo-locale");
+        assert!(
+            !LocaleFileKind::ALL
+                .iter()
+                .any(|kind| pseudo.has_file(*kind))
+        );
+        assert!(pseudo.is_complete());
+    }
+
+    #[test]
+    fn shipped_manifest_lists_every_locale() {
+        let locales = Path::new(env!("CARGO_MANIFEST_DIR")).join(LOCALES_PATH);
+        let manifest = fs::read_to_string(locales.join(MANIFEST_FILE_NAME)).unwrap();
+        let manifest: LocaleManifest = serde_json::from_str(&manifest).unwrap();
+        assert_eq!(
+            manifest,
+            LocaleManifest::scan(&locales).unwrap(),
+            "run `locale-lint --write-manifest`"
+        );
+    }
+}
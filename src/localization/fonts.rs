@@ -0,0 +1,393 @@
+use ab_glyph::Font as _;
+use bevy::asset::LoadState;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use fluent::FluentResource;
+use fluent_syntax::ast;
+use std::collections::{BTreeSet, HashMap};
+
+use super::pseudo::{self, PSEUDO_LANG};
+use super::{CurrentLang, LocaleFile, LocaleFiles, REFERENCE_LANG};
+use crate::text_generator::{
+    DictionariesAsset, TemplatesAsset, TextGeneratorFiles, generated_glyphs,
+};
+
+pub const ARBUTUS_FONT: &str = "fonts/Arbutus-Regular.ttf";
+pub const LATO_FONT: &str = "fonts/Lato-Regular.ttf";
+pub const OPEN_DYSLEXIC_FONT: &str = "fonts/OpenDyslexicMNerdFont-Regular.otf";
+
+/// What a piece of text is used for; each role gets its own font.
+/// Text entities carrying this component get the current language's font for it.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextRole {
+    Title,
+    Body,
+    Button,
+}
+
+impl TextRole {
+    pub const ALL: [TextRole; 3] = [Self::Title, Self::Body, Self::Button];
+}
+
+/// Which font file each role uses, optionally per language.
+#[derive(Resource, Debug, Clone)]
+pub struct LocaleFontConfig {
+    pub defaults: HashMap<TextRole, String>,
+    /// Overrides of `defaults` for a single language.
+    pub overrides: HashMap<(String, TextRole), String>,
+    /// Tried in order when the chosen font lacks glyphs the language needs.
+    pub fallbacks: Vec<String>,
+}
+
+impl Default for LocaleFontConfig {
+    fn default() -> Self {
+        Self {
+            defaults: HashMap::from([
+                (TextRole::Title, ARBUTUS_FONT.to_string()),
+                (TextRole::Body, LATO_FONT.to_string()),
+                (TextRole::Button, LATO_FONT.to_string()),
+            ]),
+            overrides: HashMap::new(),
+            fallbacks: vec![
+                LATO_FONT.to_string(),
+                OPEN_DYSLEXIC_FONT.to_string(),
+                ARBUTUS_FONT.to_string(),
+            ],
+        }
+    }
+}
+
+impl LocaleFontConfig {
+    /// Fonts to try for `role` in `lang`, best first, without duplicates.
+    fn candidates(&self, lang: &str, role: TextRole) -> Vec<&str> {
+        let mut candidates: Vec<&str> = Vec::new();
+        let preferred = self
+            .overrides
+            .get(&(lang.to_string(), role))
+            .or_else(|| self.defaults.get(&role));
+        for path in preferred.into_iter().chain(&self.fallbacks) {
+            if !candidates.contains(&path.as_str()) {
+                candidates.push(path);
+            }
+        }
+        candidates
+    }
+
+    fn paths(&self) -> BTreeSet<&str> {
+        self.defaults
+            .values()
+            .chain(self.overrides.values())
+            .chain(&self.fallbacks)
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Fonts picked for the current language, one per role.
+/// Empty until the fonts and the language's locale files have loaded.
+#[derive(Resource, Debug, Default)]
+pub struct LocaleFonts {
+    lang: String,
+    fonts: HashMap<TextRole, Handle<Font>>,
+    handles: HashMap<String, Handle<Font>>,
+}
+
+impl LocaleFonts {
+    pub fn get(&self, role: TextRole) -> Option<&Handle<Font>> {
+        self.fonts.get(&role)
+    }
+
+    /// The language the fonts were picked for.
+    pub fn lang(&self) -> &str {
+        &self.lang
+    }
+
+    /// Whether every configured font has either loaded or failed.
+    pub fn all_settled(&self, asset_server: &AssetServer) -> bool {
+        self.handles.values().all(|handle| {
+            matches!(
+                asset_server.load_state(handle),
+                LoadState::Loaded | LoadState::Failed
+            )
+        })
+    }
+}
+
+/// The locale files fonts are picked from.
+#[derive(SystemParam)]
+pub(super) struct GlyphSources<'w> {
+    locale_files: Res<'w, LocaleFiles>,
+    locale_assets: Res<'w, Assets<LocaleFile>>,
+    text_files: Res<'w, TextGeneratorFiles>,
+    dictionaries: Res<'w, Assets<DictionariesAsset>>,
+    templates: Res<'w, Assets<TemplatesAsset>>,
+    asset_server: Res<'w, AssetServer>,
+}
+
+/// Characters players can see in a language: the messages of its `game.ftl`
+/// and whatever the text generator can make from its dictionaries and
+/// templates. The pseudo-locale needs the accented form of the reference
+/// language's text. `None` until all of them have loaded.
+fn required_glyphs(lang: &str, sources: &GlyphSources) -> Option<BTreeSet<char>> {
+    let is_pseudo = lang == PSEUDO_LANG;
+    let source_lang = if is_pseudo { REFERENCE_LANG } else { lang };
+    let mut glyphs = BTreeSet::new();
+    let mut any_loaded = false;
+
+    if sources.locale_files.handle(source_lang).is_some() {
+        let source = sources
+            .locale_files
+            .text(source_lang, &sources.locale_assets)?;
+        any_loaded = true;
+        for text in fluent_visible_text(source) {
+            if is_pseudo {
+                glyphs.extend(pseudo::pseudolocalize(&text).chars());
+            } else {
+                glyphs.extend(text.chars());
+            }
+        }
+        // Numbers formatted into messages.
+        glyphs.extend('0'..='9');
+    }
+
+    if let (Some(dictionaries), Some(templates)) = (
+        sources
+            .text_files
+            .dictionaries(source_lang, &sources.dictionaries),
+        sources
+            .text_files
+            .templates(source_lang, &sources.templates),
+    ) {
+        any_loaded = true;
+        let reference = if source_lang == REFERENCE_LANG {
+            None
+        } else {
+            sources
+                .text_files
+                .dictionaries(REFERENCE_LANG, &sources.dictionaries)
+        };
+        glyphs.extend(generated_glyphs(
+            source_lang,
+            dictionaries,
+            templates,
+            reference,
+            is_pseudo,
+        ));
+    } else if sources
+        .text_files
+        .is_loading(source_lang, &sources.asset_server)
+    {
+        return None;
+    }
+
+    glyphs.retain(|c| !c.is_whitespace() && !c.is_control());
+    any_loaded.then_some(glyphs)
+}
+
+/// Text of Fluent `source` that can end up on screen: the values and
+/// attributes of messages and terms, including every select variant and
+/// string literal. Comments, ids, references and variables are left out.
+fn fluent_visible_text(source: &str) -> Vec<String> {
+    // Syntax errors are reported when the bundle is built; recovered entries are enough here.
+    let resource =
+        FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, _)| resource);
+    let mut texts = Vec::new();
+    for entry in resource.entries() {
+        let (value, attributes) = match entry {
+            ast::Entry::Message(message) => (message.value.as_ref(), &message.attributes),
+            ast::Entry::Term(term) => (Some(&term.value), &term.attributes),
+            _ => continue,
+        };
+        for pattern in value
+            .into_iter()
+            .chain(attributes.iter().map(|attribute| &attribute.value))
+        {
+            collect_pattern_text(pattern, &mut texts);
+        }
+    }
+    texts
+}
+
+fn collect_pattern_text(pattern: &ast::Pattern<&str>, texts: &mut Vec<String>) {
+    for element in &pattern.elements {
+        match element {
+            ast::PatternElement::TextElement { value } => texts.push(value.to_string()),
+            ast::PatternElement::Placeable { expression } => {
+                collect_expression_text(expression, texts)
+            }
+        }
+    }
+}
+
+fn collect_expression_text(expression: &ast::Expression<&str>, texts: &mut Vec<String>) {
+    match expression {
+        ast::Expression::Select { variants, .. } => {
+            for variant in variants {
+                collect_pattern_text(&variant.value, texts);
+            }
+        }
+        ast::Expression::Inline(ast::InlineExpression::StringLiteral { value }) => {
+            texts.push(value.to_string())
+        }
+        ast::Expression::Inline(ast::InlineExpression::Placeable { expression }) => {
+            collect_expression_text(expression, texts)
+        }
+        ast::Expression::Inline(_) => {}
+    }
+}
+
+/// Glyphs from `required` that `font` has no outline for.
+fn missing_glyphs(font: &Font, required: &BTreeSet<char>) -> String {
+    required
+        .iter()
+        .filter(|c| font.font.glyph_id(**c).0 == 0)
+        .collect()
+}
+
+pub(super) fn load_fonts(
+    asset_server: Res<AssetServer>,
+    config: Res<LocaleFontConfig>,
+    mut locale_fonts: ResMut<LocaleFonts>,
+) {
+    for path in config.paths() {
+        locale_fonts
+            .handles
+            .insert(path.to_string(), asset_server.load(path.to_string()));
+    }
+}
+
+/// Picks a font per role for the current language once everything it needs has
+/// loaded, preferring the configured font and falling back to the first one
+/// that has every glyph the language's text uses.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn resolve_locale_fonts(
+    mut font_events: EventReader<AssetEvent<Font>>,
+    mut locale_file_events: EventReader<AssetEvent<LocaleFile>>,
+    mut dictionary_events: EventReader<AssetEvent<DictionariesAsset>>,
+    mut template_events: EventReader<AssetEvent<TemplatesAsset>>,
+    current_lang: Res<CurrentLang>,
+    config: Res<LocaleFontConfig>,
+    sources: GlyphSources,
+    fonts: Res<Assets<Font>>,
+    mut locale_fonts: ResMut<LocaleFonts>,
+) {
+    // Every reader is drained, so events seen this frame do not come back next frame.
+    let changed_counts = [
+        font_events.read().count(),
+        locale_file_events.read().count(),
+        dictionary_events.read().count(),
+        template_events.read().count(),
+    ];
+    let assets_changed = changed_counts.iter().any(|count| *count > 0);
+    let up_to_date = locale_fonts.lang == current_lang.0;
+    if up_to_date && !assets_changed && !config.is_changed() {
+        return;
+    }
+    if !locale_fonts.all_settled(&sources.asset_server) {
+        return;
+    }
+    let lang = current_lang.0.as_str();
+    let Some(required) = required_glyphs(lang, &sources) else {
+        return;
+    };
+
+    let mut picked = HashMap::new();
+    for role in TextRole::ALL {
+        let candidates = config.candidates(lang, role);
+        let mut rejected = Vec::new();
+        let mut chosen = None;
+        for path in &candidates {
+            let Some(handle) = locale_fonts.handles.get(*path) else {
+                continue;
+            };
+            // Fonts that failed to load are skipped; the asset server has logged why.
+            let Some(font) = fonts.get(handle) else {
+                continue;
+            };
+            let missing = missing_glyphs(font, &required);
+            if missing.is_empty() {
+                chosen = Some(handle.clone());
+                break;
+            }
+            rejected.push((*path, missing));
+        }
+        if chosen.is_none() {
+            // Nothing covers the language; the first loaded candidate is still better than no font.
+            chosen = candidates
+                .iter()
+                .filter_map(|path| locale_fonts.handles.get(*path))
+                .find(|handle| fonts.contains(*handle))
+                .cloned();
+            error!(
+                "Fonts: no font covers every glyph of '{}' for {:?} text",
+                lang, role
+            );
+        }
+        let Some(handle) = chosen else {
+            // Every candidate failed to load; the role keeps Bevy's default font.
+            continue;
+        };
+        picked.insert(role, handle);
+        for (path, missing) in rejected {
+            warn!(
+                "Fonts: {} lacks glyphs '{}' needed by '{}', falling back for {:?} text",
+                path, missing, lang, role
+            );
+        }
+    }
+    locale_fonts.lang = lang.to_string();
+    locale_fonts.fonts = picked;
+}
+
+pub(super) fn apply_locale_fonts(
+    locale_fonts: Res<LocaleFonts>,
+    mut texts: Query<(Ref<TextRole>, &mut Text)>,
+) {
+    for (role, mut text) in &mut texts {
+        if !locale_fonts.is_changed() && !role.is_changed() {
+            continue;
+        }
+        let Some(font) = locale_fonts.get(*role) else {
+            continue;
+        };
+        for section in &mut text.sections {
+            section.style.font = font.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text_generator::WhackaMoleeGenerator;
+
+    #[test]
+    fn glyphs_come_from_visible_text() {
+        let source = "# Zażółć gęślą jaźń\nmain-menu-title = Whack {$name}!\n    .tooltip = Tip\n\
+                      moles = {$count ->\n    [one] one møle\n   *[other] {\"many\"}\n}\n";
+        let glyphs: BTreeSet<char> = fluent_visible_text(source).concat().chars().collect();
+        for seen in ['W', '!', 'T', 'ø', 'y'] {
+            assert!(glyphs.contains(&seen), "{}", seen);
+        }
+        for markup in ['#', 'ż', '-', '$', '{', '[', '*', '"'] {
+            assert!(!glyphs.contains(&markup), "{}", markup);
+        }
+
+        let templates = r#"{
+            "TAGLINE_TEMPLATES": ["{NOUN_VEGETABLE|capitalize}!"],
+            "TEAM_NAME_TEMPLATES": ["x"],
+            "TERRAIN_NAME_TEMPLATES": ["x"]
+        }"#;
+        let generator = WhackaMoleeGenerator::from_json(
+            "en",
+            r#"{ "NOUN_VEGETABLE": ["onion"] }"#,
+            templates,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            generator.glyphs(),
+            BTreeSet::from(['!', 'O', 'i', 'n', 'o', 'x'])
+        );
+    }
+}
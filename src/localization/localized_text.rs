@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use fluent::FluentArgs;
+
+use super::FluentBundleResource;
+
+/// Argument value passed to a localized message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LocalizedArg {
+    Number(f64),
+    String(String),
+}
+
+impl From<f64> for LocalizedArg {
+    fn from(value: f64) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl From<i32> for LocalizedArg {
+    fn from(value: i32) -> Self {
+        Self::Number(value.into())
+    }
+}
+
+impl From<u32> for LocalizedArg {
+    fn from(value: u32) -> Self {
+        Self::Number(value.into())
+    }
+}
+
+impl From<&str> for LocalizedArg {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for LocalizedArg {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+/// Keeps the first section of the entity's `Text` set to a translated message.
+/// The text is refreshed when the component changes and whenever the active
+/// language or its bundle changes, so UI never has to be rebuilt for it.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct LocalizedText {
+    pub key: String,
+    pub args: Vec<(String, LocalizedArg)>,
+}
+
+impl LocalizedText {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            args: Vec::new(),
+        }
+    }
+
+    pub fn with_arg(mut self, name: impl Into<String>, value: impl Into<LocalizedArg>) -> Self {
+        self.set_arg(name, value);
+        self
+    }
+
+    /// Sets or replaces an argument.
+    pub fn set_arg(&mut self, name: impl Into<String>, value: impl Into<LocalizedArg>) {
+        let name = name.into();
+        let value = value.into();
+        match self.args.iter_mut().find(|(arg_name, _)| *arg_name == name) {
+            Some((_, arg)) => *arg = value,
+            None => self.args.push((name, value)),
+        }
+    }
+
+    fn fluent_args(&self) -> Option<FluentArgs<'_>> {
+        if self.args.is_empty() {
+            return None;
+        }
+        let mut args = FluentArgs::new();
+        for (name, value) in &self.args {
+            match value {
+                LocalizedArg::Number(number) => args.set(name.as_str(), *number),
+                LocalizedArg::String(text) => args.set(name.as_str(), text.as_str()),
+            }
+        }
+        Some(args)
+    }
+
+    pub fn translate(&self, bundles: &FluentBundleResource) -> String {
+        bundles.translate(&self.key, self.fluent_args().as_ref())
+    }
+}
+
+pub(super) fn update_localized_texts(
+    bundles: Res<FluentBundleResource>,
+    mut texts: Query<(Ref<LocalizedText>, &mut Text)>,
+) {
+    let bundles_changed = bundles.is_changed();
+    for (localized, mut text) in &mut texts {
+        if !bundles_changed && !localized.is_changed() {
+            continue;
+        }
+        let value = localized.translate(&bundles);
+        match text.sections.first_mut() {
+            Some(section) => section.value = value,
+            None => text.sections.push(TextSection::from(value)),
+        }
+    }
+}
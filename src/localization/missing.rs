@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use once_cell::sync::Lazy;
+use std::{collections::BTreeSet, sync::Mutex};
+
+use super::REFERENCE_LANG;
+
+/// Something a locale lacks compared to the reference language.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MissingTranslation {
+    /// A Fluent message that was served from the reference bundle.
+    Message { lang: String, key: String },
+    /// A whole dictionary that was taken from the reference dictionaries.
+    DictionaryKey { lang: String, key: String },
+    /// A dictionary that has `missing` fewer entries than the reference one.
+    DictionaryEntries {
+        lang: String,
+        key: String,
+        missing: usize,
+    },
+}
+
+impl MissingTranslation {
+    pub fn lang(&self) -> &str {
+        match self {
+            Self::Message { lang, .. }
+            | Self::DictionaryKey { lang, .. }
+            | Self::DictionaryEntries { lang, .. } => lang,
+        }
+    }
+}
+
+impl std::fmt::Display for MissingTranslation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Message { lang, key } => {
+                write!(
+                    f,
+                    "'{}' uses '{}' for message '{}'",
+                    lang, REFERENCE_LANG, key
+                )
+            }
+            Self::DictionaryKey { lang, key } => {
+                write!(
+                    f,
+                    "'{}' uses '{}' for dictionary '{}'",
+                    lang, REFERENCE_LANG, key
+                )
+            }
+            Self::DictionaryEntries { lang, key, missing } => write!(
+                f,
+                "'{}' dictionary '{}' has {} fewer entries than '{}'",
+                lang, key, missing, REFERENCE_LANG
+            ),
+        }
+    }
+}
+
+/// Every fallback taken so far, for translators to fill in.
+#[derive(Resource, Debug, Default)]
+pub struct MissingTranslations {
+    entries: BTreeSet<MissingTranslation>,
+}
+
+impl MissingTranslations {
+    /// Records a fallback, logging it the first time it is seen.
+    pub fn record(&mut self, missing: MissingTranslation) {
+        if !self.entries.contains(&missing) {
+            warn!("Localization: {}", missing);
+            self.entries.insert(missing);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &MissingTranslation> {
+        self.entries.iter()
+    }
+
+    pub fn for_lang<'a>(&'a self, lang: &'a str) -> impl Iterator<Item = &'a MissingTranslation> {
+        self.entries
+            .iter()
+            .filter(move |missing| missing.lang() == lang)
+    }
+}
+
+/// Fallbacks taken by [`super::translate`], which runs outside the ECS world.
+static PENDING: Lazy<Mutex<BTreeSet<MissingTranslation>>> =
+    Lazy::new(|| Mutex::new(BTreeSet::new()));
+
+pub(super) fn report(missing: MissingTranslation) {
+    PENDING.lock().unwrap().insert(missing);
+}
+
+pub(super) fn collect_missing_translations(mut missing_translations: ResMut<MissingTranslations>) {
+    let pending = std::mem::take(&mut *PENDING.lock().unwrap());
+    for missing in pending {
+        missing_translations.record(missing);
+    }
+}
@@ -0,0 +1,515 @@
+use bevy::prelude::*;
+use fluent::concurrent::FluentBundle;
+use fluent::{FluentArgs, FluentResource};
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    sync::{Arc, RwLock},
+};
+use unic_langid::LanguageIdentifier;
+
+use crate::settings::UserSettings;
+
+pub mod assets;
+pub mod discovery;
+pub mod fonts;
+pub mod localized_text;
+pub mod missing;
+pub mod number_format;
+pub mod pseudo;
+
+pub use assets::{LocaleFile, LocaleFileKind, LocaleFiles};
+pub use discovery::{AvailableLanguages, LocaleManifest};
+pub use fonts::{LocaleFontConfig, LocaleFonts, TextRole};
+pub use localized_text::{LocalizedArg, LocalizedText};
+pub use missing::{MissingTranslation, MissingTranslations};
+pub use pseudo::PSEUDO_LANG;
+
+/// Directory holding one sub-directory per language.
+pub const LOCALES_PATH: &str = "assets/locales";
+/// Fluent file loaded from every language directory.
+pub const FLUENT_FILE_NAME: &str = "game.ftl";
+/// Language every other locale is compared against.
+pub const REFERENCE_LANG: &str = "en";
+
+pub type LocaleBundle = FluentBundle<FluentResource>;
+
+#[derive(Resource, Default)]
+pub struct LocalizationResource;
+
+#[derive(Resource, Clone, Debug)]
+pub struct CurrentLang(pub String);
+
+impl Default for CurrentLang {
+    fn default() -> Self {
+        Self(REFERENCE_LANG.to_string())
+    }
+}
+
+/// Fluent bundles for every loaded language, plus the one currently in use.
+#[derive(Resource, Default)]
+pub struct FluentBundleResource {
+    bundles: HashMap<String, Arc<LocaleBundle>>,
+    active: Option<ActiveBundles>,
+}
+
+/// The bundle of the current language and the reference bundle behind it.
+#[derive(Clone)]
+struct ActiveBundles {
+    lang: String,
+    primary: Arc<LocaleBundle>,
+    fallback: Option<Arc<LocaleBundle>>,
+}
+
+impl ActiveBundles {
+    /// Formats `key` from the primary bundle, falling back to the reference
+    /// language and then to the key itself.
+    fn translate(&self, key: &str, args: Option<&FluentArgs>) -> String {
+        let text = if let Some(text) = format_message(&self.primary, key, args) {
+            text
+        } else if let Some(text) = self
+            .fallback
+            .as_ref()
+            .and_then(|fallback| format_message(fallback, key, args))
+        {
+            missing::report(MissingTranslation::Message {
+                lang: self.lang.clone(),
+                key: key.to_string(),
+            });
+            text
+        } else {
+            warn!("Localization: missing message '{}'", key);
+            return key.to_string();
+        };
+        if self.lang == PSEUDO_LANG {
+            pseudo::wrap(&text)
+        } else {
+            text
+        }
+    }
+}
+
+impl FluentBundleResource {
+    /// Builds a bundle for each language from `<base_path>/<lang>/game.ftl`.
+    /// Languages whose file cannot be read are skipped with an error.
+    pub fn load(base_path: &Path, langs: &[&str]) -> Self {
+        let mut bundles = HashMap::new();
+        for lang in langs {
+            match load_bundle(base_path, lang) {
+                Ok(bundle) => {
+                    bundles.insert(lang.to_string(), Arc::new(bundle));
+                }
+                Err(e) => error!("Localization: failed to load bundle for '{}': {}", lang, e),
+            }
+        }
+        Self {
+            bundles,
+            active: None,
+        }
+    }
+
+    /// Adds or replaces the bundle for `lang`, refreshing the active bundles if they use it.
+    pub fn insert_bundle(&mut self, lang: &str, bundle: LocaleBundle, current_lang: &str) {
+        self.bundles.insert(lang.to_string(), Arc::new(bundle));
+        if lang == current_lang || lang == REFERENCE_LANG {
+            self.set_active(current_lang);
+        }
+    }
+
+    pub fn get(&self, lang: &str) -> Option<&Arc<LocaleBundle>> {
+        self.bundles.get(lang)
+    }
+
+    pub fn active_lang(&self) -> &str {
+        self.active
+            .as_ref()
+            .map_or("", |active| active.lang.as_str())
+    }
+
+    /// Makes `lang` the active bundle for this resource and for [`translate`],
+    /// with the reference language behind it for missing messages.
+    /// Returns `false` (leaving the active bundle untouched) if `lang` is not loaded.
+    pub fn set_active(&mut self, lang: &str) -> bool {
+        let Some(primary) = self.bundles.get(lang) else {
+            return false;
+        };
+        let fallback = if lang == REFERENCE_LANG {
+            None
+        } else {
+            self.bundles.get(REFERENCE_LANG).cloned()
+        };
+        let active = ActiveBundles {
+            lang: lang.to_string(),
+            primary: primary.clone(),
+            fallback,
+        };
+        *ACTIVE_BUNDLES.write().unwrap() = Some(active.clone());
+        self.active = Some(active);
+        true
+    }
+
+    /// Translates `key` with the active bundle.
+    pub fn translate(&self, key: &str, args: Option<&FluentArgs>) -> String {
+        match &self.active {
+            Some(active) => active.translate(key, args),
+            None => key.to_string(),
+        }
+    }
+}
+
+/// Bundles used by [`translate`] and `t!`, which have no access to the ECS world.
+static ACTIVE_BUNDLES: Lazy<RwLock<Option<ActiveBundles>>> = Lazy::new(|| RwLock::new(None));
+
+fn load_bundle(base_path: &Path, lang: &str) -> Result<LocaleBundle, Box<dyn std::error::Error>> {
+    let ftl_path = base_path.join(lang).join(FLUENT_FILE_NAME);
+    let source = fs::read_to_string(&ftl_path)?;
+    build_bundle(lang, &ftl_path, source)
+}
+
+/// Builds a bundle from Fluent source; `ftl_path` is only used in error messages.
+fn build_bundle(
+    lang: &str,
+    ftl_path: &Path,
+    source: String,
+) -> Result<LocaleBundle, Box<dyn std::error::Error>> {
+    let langid: LanguageIdentifier = lang.parse()?;
+    let mut bundle = LocaleBundle::new_concurrent(vec![langid]);
+    // Bevy text has no bidi support, so the isolation marks would render as boxes.
+    bundle.set_use_isolating(false);
+    bundle.set_formatter(Some(number_format::fluent_formatter));
+    let resource = parse_fluent_resource(ftl_path, source);
+    if let Err(errors) = bundle.add_resource(resource) {
+        for e in errors {
+            error!("Localization: {}: {}", ftl_path.display(), e);
+        }
+    }
+    Ok(bundle)
+}
+
+/// Builds the pseudo-locale bundle from the reference language's Fluent source.
+/// The bundle keeps the reference language's identifier so plurals and numbers
+/// are formatted as in the source; only the literal text is accented.
+fn build_pseudo_bundle(
+    ftl_path: &Path,
+    source: String,
+) -> Result<LocaleBundle, Box<dyn std::error::Error>> {
+    let mut bundle = build_bundle(REFERENCE_LANG, ftl_path, source)?;
+    bundle.set_transform(Some(pseudo::fluent_transform));
+    Ok(bundle)
+}
+
+/// Parses Fluent source, logging every syntax error with its file and line.
+/// Fluent recovers from errors, so the valid entries are still returned.
+fn parse_fluent_resource(path: &Path, source: String) -> FluentResource {
+    FluentResource::try_new(source).unwrap_or_else(|(resource, errors)| {
+        for e in errors {
+            let (line, column) = line_and_column(resource.source(), e.pos.start);
+            error!(
+                "Localization: {}:{}:{}: {}",
+                path.display(),
+                line,
+                column,
+                e
+            );
+        }
+        resource
+    })
+}
+
+/// 1-based line and column of a byte offset.
+pub fn line_and_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rfind('\n')
+        .map_or(before.len(), |pos| before.len() - pos - 1)
+        + 1;
+    (line, column)
+}
+
+/// Formats `key` from a single bundle, or `None` if the bundle lacks it.
+fn format_message(bundle: &LocaleBundle, key: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let pattern = bundle.get_message(key)?.value()?;
+    let mut errors = vec![];
+    let text = bundle
+        .format_pattern(pattern, args, &mut errors)
+        .into_owned();
+    for e in errors {
+        warn!("Localization: error formatting '{}': {}", key, e);
+    }
+    Some(text)
+}
+
+#[derive(Event)]
+pub struct LanguageChangeRequest(pub String);
+
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub enum LocalizationSystemSet {
+    /// Reads the locale manifest into [`AvailableLanguages`].
+    Discovery,
+    LanguageProcessing,
+}
+
+/// Macro `t!` for retrieving translated texts
+#[macro_export]
+macro_rules! t {
+    ($key:expr) => {
+        $crate::localization::translate($key, None)
+    };
+    ($key:expr, $( $k:expr => $v:expr ),+ $(,)?) => {{
+        let mut args = fluent::FluentArgs::new();
+        $( args.set($k, $v); )+
+        $crate::localization::translate($key, Some(args))
+    }};
+    ($bundle_res:expr, $key:expr) => {
+        $bundle_res.translate($key, None)
+    };
+    ($bundle_res:expr, $key:expr, $( $k:expr => $v:expr ),+ $(,)?) => {{
+        let mut args = fluent::FluentArgs::new();
+        $( args.set($k, $v); )+
+        $bundle_res.translate($key, Some(&args))
+    }};
+}
+
+/// Function to translate text with optional Fluent arguments
+pub fn translate(key: &str, args: Option<FluentArgs>) -> String {
+    match ACTIVE_BUNDLES.read().unwrap().as_ref() {
+        Some(active) => active.translate(key, args.as_ref()),
+        None => key.to_string(),
+    }
+}
+
+/// Localization plugin
+pub struct LocalizationPlugin;
+
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(UserSettings::load())
+            .init_resource::<AvailableLanguages>()
+            .init_resource::<LocalizationResource>()
+            .init_resource::<CurrentLang>()
+            .init_resource::<FluentBundleResource>()
+            .init_resource::<MissingTranslations>()
+            .init_resource::<LocaleFiles>()
+            .init_resource::<LocaleFontConfig>()
+            .init_resource::<LocaleFonts>()
+            .init_asset::<LocaleFile>()
+            .init_asset::<LocaleManifest>()
+            .init_asset_loader::<assets::LocaleFileLoader>()
+            .init_asset_loader::<assets::LocaleManifestLoader>()
+            .add_event::<LanguageChangeRequest>()
+            .configure_sets(
+                Update,
+                (
+                    LocalizationSystemSet::Discovery,
+                    LocalizationSystemSet::LanguageProcessing,
+                )
+                    .chain(),
+            )
+            .add_systems(Startup, (assets::load_locale_manifest, fonts::load_fonts))
+            .add_systems(
+                Update,
+                assets::discover_languages.in_set(LocalizationSystemSet::Discovery),
+            )
+            .add_systems(
+                Update,
+                (reload_changed_bundles, handle_language_change)
+                    .chain()
+                    .in_set(LocalizationSystemSet::LanguageProcessing),
+            )
+            .add_systems(
+                Update,
+                (
+                    localized_text::update_localized_texts,
+                    (fonts::resolve_locale_fonts, fonts::apply_locale_fonts).chain(),
+                )
+                    .after(LocalizationSystemSet::LanguageProcessing),
+            )
+            .add_systems(Last, missing::collect_missing_translations);
+    }
+}
+
+/// Picks the starting language: the one saved in the user settings, else the
+/// one matching the system locale, else the reference language.
+fn pick_starting_language(
+    available: &AvailableLanguages,
+    settings: &UserSettings,
+    current_lang: &mut CurrentLang,
+) {
+    let saved = settings.language.as_deref().filter(|lang| {
+        let known = available.contains(lang);
+        if !known {
+            warn!(
+                "Localization: saved language '{}' is no longer available",
+                lang
+            );
+        }
+        known
+    });
+    if let Some(lang) = saved {
+        current_lang.0 = lang.to_string();
+    } else if let Some(language) = available.detect_system_language() {
+        current_lang.0 = language.code.clone();
+    }
+    info!("Setting up localization with language: {}", current_lang.0);
+}
+
+/// Rebuilds the bundle of every `game.ftl` that finished loading or changed on
+/// disk, and records its messages in [`AvailableLanguages`].
+fn reload_changed_bundles(
+    mut events: EventReader<AssetEvent<LocaleFile>>,
+    locale_files: Res<LocaleFiles>,
+    locale_assets: Res<Assets<LocaleFile>>,
+    mut available: ResMut<AvailableLanguages>,
+    current_lang: Res<CurrentLang>,
+    mut bundles: ResMut<FluentBundleResource>,
+) {
+    for lang in assets::changed_locale_files(&mut events, &locale_files) {
+        let Some(source) = locale_files.text(lang, &locale_assets) else {
+            continue;
+        };
+        available.record_messages(lang, source);
+        let ftl_path = Path::new(LOCALES_PATH).join(lang).join(FLUENT_FILE_NAME);
+        match build_bundle(lang, &ftl_path, source.to_string()) {
+            Ok(bundle) => {
+                info!("Localization: loaded bundle for '{}'", lang);
+                bundles.insert_bundle(lang, bundle, &current_lang.0);
+            }
+            Err(e) => error!("Localization: failed to build bundle for '{}': {}", lang, e),
+        }
+        if lang == REFERENCE_LANG && available.contains(PSEUDO_LANG) {
+            match build_pseudo_bundle(&ftl_path, source.to_string()) {
+                Ok(bundle) => bundles.insert_bundle(PSEUDO_LANG, bundle, &current_lang.0),
+                Err(e) => error!("Localization: failed to build pseudo-locale bundle: {}", e),
+            }
+        }
+    }
+}
+
+/// Handle language change requests
+fn handle_language_change(
+    mut events: EventReader<LanguageChangeRequest>,
+    available: Res<AvailableLanguages>,
+    mut current_lang: ResMut<CurrentLang>,
+    mut bundles: ResMut<FluentBundleResource>,
+    mut settings: ResMut<UserSettings>,
+) {
+    for event in events.read() {
+        if !available.contains(&event.0) {
+            warn!(
+                "Localization: ignoring change to unknown language '{}'",
+                event.0
+            );
+            continue;
+        }
+        info!("Changing language to: {}", event.0);
+        if !bundles.set_active(&event.0) {
+            warn!("Localization: no bundle loaded for language '{}'", event.0);
+        }
+        current_lang.0 = event.0.clone();
+
+        if settings.language.as_deref() != Some(event.0.as_str()) {
+            settings.language = Some(event.0.clone());
+            if let Err(e) = settings.save() {
+                warn!("Localization: could not save language choice: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shipped_bundles() -> FluentBundleResource {
+        let base_path = Path::new(env!("CARGO_MANIFEST_DIR")).join(LOCALES_PATH);
+        FluentBundleResource::load(&base_path, &["en", "es", "pl"])
+    }
+
+    fn moles_remaining(bundles: &FluentBundleResource, lang: &str, count: f64) -> String {
+        let mut args = FluentArgs::new();
+        args.set("count", count);
+        format_message(
+            bundles.get(lang).unwrap(),
+            "match-moles-remaining",
+            Some(&args),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn english_plural_categories() {
+        let bundles = shipped_bundles();
+        assert_eq!(moles_remaining(&bundles, "en", 1.0), "1 mole remaining");
+        assert_eq!(moles_remaining(&bundles, "en", 0.0), "0 moles remaining");
+        assert_eq!(moles_remaining(&bundles, "en", 2.0), "2 moles remaining");
+        assert_eq!(moles_remaining(&bundles, "en", 1.5), "1.5 moles remaining");
+    }
+
+    #[test]
+    fn spanish_plural_categories() {
+        let bundles = shipped_bundles();
+        assert_eq!(moles_remaining(&bundles, "es", 1.0), "Queda 1 topo");
+        assert_eq!(moles_remaining(&bundles, "es", 0.0), "Quedan 0 topos");
+        assert_eq!(moles_remaining(&bundles, "es", 7.0), "Quedan 7 topos");
+        assert_eq!(moles_remaining(&bundles, "es", 2.5), "Quedan 2,5 topos");
+    }
+
+    #[test]
+    fn polish_plural_categories() {
+        let bundles = shipped_bundles();
+        assert_eq!(moles_remaining(&bundles, "pl", 1.0), "Został 1 kret");
+        for few in [2.0, 3.0, 4.0, 22.0] {
+            assert_eq!(
+                moles_remaining(&bundles, "pl", few),
+                format!("Zostały {} krety", few)
+            );
+        }
+        for many in [0.0, 5.0, 12.0, 14.0, 25.0] {
+            assert_eq!(
+                moles_remaining(&bundles, "pl", many),
+                format!("Zostało {} kretów", many)
+            );
+        }
+        assert_eq!(moles_remaining(&bundles, "pl", 1.5), "Zostało 1,5 kreta");
+    }
+
+    #[test]
+    fn numbers_follow_locale_separators() {
+        let bundles = shipped_bundles();
+        assert_eq!(
+            moles_remaining(&bundles, "en", 1234.0),
+            "1,234 moles remaining"
+        );
+        assert_eq!(moles_remaining(&bundles, "es", 1234.0), "Quedan 1234 topos");
+        assert_eq!(
+            moles_remaining(&bundles, "es", 12345.0),
+            "Quedan 12.345 topos"
+        );
+        assert_eq!(
+            moles_remaining(&bundles, "pl", 1235.0),
+            "Zostało 1235 kretów"
+        );
+        assert_eq!(
+            moles_remaining(&bundles, "pl", 12345.0),
+            "Zostało 12\u{a0}345 kretów"
+        );
+    }
+
+    #[test]
+    fn t_macro_passes_arguments() {
+        let mut bundles = shipped_bundles();
+        assert!(bundles.set_active("pl"));
+        assert_eq!(
+            t!(bundles, "match-damage-dealt", "damage" => 3),
+            "3 punkty obrażeń"
+        );
+        assert_eq!(
+            t!(bundles, "match-damage-dealt", "damage" => 15),
+            "15 punktów obrażeń"
+        );
+        assert_eq!(t!(bundles, "main-menu-start-game"), "Rozpocznij Grę");
+    }
+}
@@ -0,0 +1,128 @@
+use fluent::FluentValue;
+use fluent::types::FluentNumber;
+use intl_memoizer::Memoizable;
+use intl_memoizer::concurrent::IntlLangMemoizer;
+use unic_langid::LanguageIdentifier;
+
+/// Separators used when printing numbers in a given language (CLDR "latn" symbols).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberFormat {
+    decimal_separator: char,
+    group_separator: char,
+    /// Smallest number of digits the leading group must have before grouping kicks in,
+    /// e.g. Polish writes "1000" but "10 000".
+    min_grouping_digits: usize,
+}
+
+impl NumberFormat {
+    pub fn for_language(lang: &str) -> Self {
+        match lang {
+            "es" => Self {
+                decimal_separator: ',',
+                group_separator: '.',
+                min_grouping_digits: 2,
+            },
+            "pl" => Self {
+                decimal_separator: ',',
+                group_separator: '\u{a0}',
+                min_grouping_digits: 2,
+            },
+            _ => Self {
+                decimal_separator: '.',
+                group_separator: ',',
+                min_grouping_digits: 1,
+            },
+        }
+    }
+
+    pub fn format(&self, number: &FluentNumber) -> String {
+        let options = &number.options;
+        let min_fraction = options.minimum_fraction_digits.unwrap_or(0);
+        let digits = match options.maximum_fraction_digits {
+            Some(max_fraction) => {
+                let rounded = format!("{:.*}", max_fraction.max(min_fraction), number.value.abs());
+                trim_fraction(rounded, min_fraction)
+            }
+            None => {
+                let mut plain = number.value.abs().to_string();
+                if min_fraction > 0 {
+                    let fraction_len = plain.find('.').map_or(0, |pos| plain.len() - pos - 1);
+                    if fraction_len == 0 {
+                        plain.push('.');
+                    }
+                    plain.push_str(&"0".repeat(min_fraction.saturating_sub(fraction_len)));
+                }
+                plain
+            }
+        };
+
+        let (integer, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+        let integer = format!(
+            "{:0>width$}",
+            integer,
+            width = options.minimum_integer_digits.unwrap_or(1)
+        );
+
+        let mut out = String::new();
+        if number.value.is_sign_negative() && number.value != 0.0 {
+            out.push('-');
+        }
+        if options.use_grouping {
+            out.push_str(&self.group(&integer));
+        } else {
+            out.push_str(&integer);
+        }
+        if !fraction.is_empty() {
+            out.push(self.decimal_separator);
+            out.push_str(fraction);
+        }
+        out
+    }
+
+    fn group(&self, integer: &str) -> String {
+        if integer.len() < 4 + self.min_grouping_digits - 1 {
+            return integer.to_string();
+        }
+        let mut out = String::with_capacity(integer.len() + integer.len() / 3);
+        for (i, digit) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i).is_multiple_of(3) {
+                out.push(self.group_separator);
+            }
+            out.push(digit);
+        }
+        out
+    }
+}
+
+/// Drops trailing fraction zeros beyond `min_fraction` digits.
+fn trim_fraction(mut digits: String, min_fraction: usize) -> String {
+    if let Some(pos) = digits.find('.') {
+        let keep = pos + 1 + min_fraction;
+        while digits.len() > keep && digits.ends_with('0') {
+            digits.pop();
+        }
+        if digits.ends_with('.') {
+            digits.pop();
+        }
+    }
+    digits
+}
+
+impl Memoizable for NumberFormat {
+    type Args = ();
+    type Error = ();
+
+    fn construct(lang: LanguageIdentifier, _args: Self::Args) -> Result<Self, Self::Error> {
+        Ok(Self::for_language(lang.language.as_str()))
+    }
+}
+
+/// Fluent formatter hook that prints numbers with the bundle's locale separators.
+pub fn fluent_formatter(value: &FluentValue, intls: &IntlLangMemoizer) -> Option<String> {
+    let FluentValue::Number(number) = value else {
+        return None;
+    };
+    intls
+        .with_try_get::<NumberFormat, _, _>((), |format| format.format(number))
+        .ok()
+}
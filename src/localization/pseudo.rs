@@ -0,0 +1,93 @@
+use std::borrow::Cow;
+
+/// Code of the pseudo-locale, a synthetic language derived from the reference
+/// language to spot hardcoded strings and text overflowing its UI element.
+/// Taken from the range reserved for private use.
+pub const PSEUDO_LANG: &str = "qps";
+/// Shown in the language list instead of a name read from a `game.ftl`.
+pub const PSEUDO_DISPLAY_NAME: &str = "[Þšéûðö]";
+/// How much longer than the source a pseudo-localized string gets.
+const PADDING_RATIO: f32 = 0.4;
+/// Cycled through to pad strings, so the extra length wraps like real words.
+const PADDING_TEXT: &str = "ļöŕéš îþšûš ðöļöŕ šîţ åŵéţ ";
+
+/// Replaces ASCII letters with accented look-alikes, keeping everything else.
+/// Only Latin-1 and Latin Extended-A letters are used, which the shipped fonts cover.
+pub fn accent(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(|c| c.is_ascii_alphabetic()) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(text.chars().map(accented).collect())
+}
+
+/// Brackets `text` and pads it by [`PADDING_RATIO`] of its length. Expects
+/// text that has already been accented, so placeable values are not touched twice.
+pub fn wrap(text: &str) -> String {
+    let length = text.chars().count();
+    let padding = (length as f32 * PADDING_RATIO).ceil() as usize;
+    let mut out = String::with_capacity(text.len() + padding * 2 + 2);
+    out.push('[');
+    out.push_str(text);
+    if padding > 0 {
+        out.push(' ');
+        out.extend(PADDING_TEXT.chars().cycle().take(padding - 1));
+    }
+    out.push(']');
+    out
+}
+
+/// Full pseudo-localization of a finished string.
+pub fn pseudolocalize(text: &str) -> String {
+    wrap(&accent(text))
+}
+
+/// Fluent transform hook; accents the literal text of messages but not their arguments.
+pub fn fluent_transform(text: &str) -> Cow<'_, str> {
+    accent(text)
+}
+
+fn accented(c: char) -> char {
+    match c {
+        'a' => 'å',
+        'c' => 'ç',
+        'd' => 'ð',
+        'e' => 'é',
+        'g' => 'ĝ',
+        'h' => 'ĥ',
+        'i' => 'î',
+        'j' => 'ĵ',
+        'k' => 'ķ',
+        'l' => 'ļ',
+        'n' => 'ñ',
+        'o' => 'ö',
+        'p' => 'þ',
+        'r' => 'ŕ',
+        's' => 'š',
+        't' => 'ţ',
+        'u' => 'û',
+        'w' => 'ŵ',
+        'y' => 'ý',
+        'z' => 'ž',
+        'A' => 'Å',
+        'C' => 'Ç',
+        'D' => 'Ð',
+        'E' => 'É',
+        'G' => 'Ĝ',
+        'H' => 'Ĥ',
+        'I' => 'Î',
+        'J' => 'Ĵ',
+        'K' => 'Ķ',
+        'L' => 'Ļ',
+        'N' => 'Ñ',
+        'O' => 'Ö',
+        'P' => 'Þ',
+        'R' => 'Ŕ',
+        'S' => 'Š',
+        'T' => 'Ţ',
+        'U' => 'Û',
+        'W' => 'Ŵ',
+        'Y' => 'Ý',
+        'Z' => 'Ž',
+        other => other,
+    }
+}
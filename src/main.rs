@@ -3,15 +3,13 @@
 // ----START OF FILE----
 use bevy::prelude::*;
 
-mod game_states;
-mod localization;
-mod text_generator;
-mod ui;
-
-use crate::ui::main_menu::MainMenuPlugin;
-use game_states::AppState;
-use localization::LocalizationPlugin;
-use text_generator::TextGeneratorPlugin;
+use whacka_molee_bevy::commentary::CommentaryPlugin;
+use whacka_molee_bevy::game_objects::terrain::TerrainPlugin;
+use whacka_molee_bevy::game_states::AppState;
+use whacka_molee_bevy::localization::{LocaleFiles, LocalizationPlugin, LocalizationSystemSet};
+use whacka_molee_bevy::text_generator::{TextGeneratorFiles, TextGeneratorPlugin};
+use whacka_molee_bevy::ui::main_menu::MainMenuPlugin;
+use whacka_molee_bevy::ui::options_menu::OptionsMenuPlugin;
 
 fn main() {
     App::new()
@@ -27,14 +25,37 @@ fn main() {
         .init_state::<AppState>()
         .add_plugins(LocalizationPlugin)
         .add_plugins(TextGeneratorPlugin)
+        .add_plugins(TerrainPlugin)
+        .add_plugins(CommentaryPlugin)
         .add_plugins(MainMenuPlugin)
-        .add_systems(Startup, initial_setup_system)
+        .add_plugins(OptionsMenuPlugin)
+        .add_systems(Startup, spawn_camera)
+        .add_systems(
+            Update,
+            finish_loading_system
+                .run_if(in_state(AppState::LoadingAssets))
+                .after(LocalizationSystemSet::LanguageProcessing),
+        )
         .run();
 }
 
-fn initial_setup_system(mut app_state: ResMut<NextState<AppState>>) {
-    app_state.set(AppState::MainMenu);
+/// The one camera, drawing both the menus and the game world.
+fn spawn_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+}
+
+/// Leaves the loading state once the locale files have loaded, so menus never show raw keys
+/// and generated names are ready.
+fn finish_loading_system(
+    locale_files: Res<LocaleFiles>,
+    text_generator_files: Res<TextGeneratorFiles>,
+    asset_server: Res<AssetServer>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    if locale_files.all_settled(&asset_server) && text_generator_files.all_settled(&asset_server) {
+        app_state.set(AppState::MainMenu);
+    }
 }
 // ----END OF FILE----
 // src/main.rs
-// version:0.0.2
\ No newline at end of file
+// version:0.0.2
@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::PathBuf};
+
+/// Directory created inside the platform config directory.
+const APP_CONFIG_DIR: &str = "whacka-molee";
+const SETTINGS_FILE_NAME: &str = "settings.json";
+
+/// Player choices kept between launches, stored as JSON in the user's config directory.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct UserSettings {
+    /// Language picked in the options screen; `None` until the player picks one.
+    pub language: Option<String>,
+}
+
+impl UserSettings {
+    /// Full path of the settings file, or `None` if the platform has no config directory.
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join(APP_CONFIG_DIR).join(SETTINGS_FILE_NAME))
+    }
+
+    /// Reads the settings file. A missing file gives the defaults; an unreadable
+    /// or malformed one is logged and also gives the defaults.
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                warn!("Settings: cannot read {}: {}", path.display(), e);
+                return Self::default();
+            }
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!("Settings: ignoring malformed {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Self::path().ok_or("no config directory on this platform")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
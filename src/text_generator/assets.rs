@@ -0,0 +1,180 @@
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext, LoadState, io::Reader};
+use bevy::prelude::*;
+use bevy::utils::BoxedFuture;
+use std::collections::HashMap;
+
+use super::{Dictionaries, Templates};
+use crate::localization::assets::LOCALES_ASSET_PATH;
+use crate::localization::{AvailableLanguages, LocaleFileKind};
+
+/// A language's `dictionaries.json`, parsed by the asset server.
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct DictionariesAsset(pub(super) Dictionaries);
+
+/// A language's `templates.json`, with every template already parsed. A broken
+/// template fails the load, and the asset server logs which one.
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct TemplatesAsset(pub(super) Templates);
+
+#[derive(Default)]
+pub struct DictionariesLoader;
+
+impl AssetLoader for DictionariesLoader {
+    type Asset = DictionariesAsset;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(DictionariesAsset(serde_json::from_slice(&bytes)?))
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["json"]
+    }
+}
+
+#[derive(Default)]
+pub struct TemplatesLoader;
+
+impl AssetLoader for TemplatesLoader {
+    type Asset = TemplatesAsset;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(TemplatesAsset(serde_json::from_slice(&bytes)?))
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["json"]
+    }
+}
+
+/// Handles to the dictionaries and templates of every available language.
+#[derive(Resource, Default)]
+pub struct TextGeneratorFiles {
+    dictionaries: HashMap<String, Handle<DictionariesAsset>>,
+    templates: HashMap<String, Handle<TemplatesAsset>>,
+}
+
+impl TextGeneratorFiles {
+    pub fn dictionaries<'a>(
+        &self,
+        lang: &str,
+        assets: &'a Assets<DictionariesAsset>,
+    ) -> Option<&'a DictionariesAsset> {
+        assets.get(self.dictionaries.get(lang)?)
+    }
+
+    pub fn templates<'a>(
+        &self,
+        lang: &str,
+        assets: &'a Assets<TemplatesAsset>,
+    ) -> Option<&'a TemplatesAsset> {
+        assets.get(self.templates.get(lang)?)
+    }
+
+    /// Whether `lang`'s dictionaries or templates are still loading.
+    pub fn is_loading(&self, lang: &str, asset_server: &AssetServer) -> bool {
+        let settled = |state| matches!(state, LoadState::Loaded | LoadState::Failed);
+        self.dictionaries
+            .get(lang)
+            .is_some_and(|handle| !settled(asset_server.load_state(handle)))
+            || self
+                .templates
+                .get(lang)
+                .is_some_and(|handle| !settled(asset_server.load_state(handle)))
+    }
+
+    /// Whether every requested file has either loaded or failed.
+    pub fn all_settled(&self, asset_server: &AssetServer) -> bool {
+        self.dictionaries
+            .values()
+            .map(|handle| asset_server.load_state(handle))
+            .chain(
+                self.templates
+                    .values()
+                    .map(|handle| asset_server.load_state(handle)),
+            )
+            .all(|state| matches!(state, LoadState::Loaded | LoadState::Failed))
+    }
+
+    /// Languages whose dictionaries finished loading or changed on disk this frame.
+    pub(super) fn changed_dictionaries(
+        &self,
+        events: &mut EventReader<AssetEvent<DictionariesAsset>>,
+    ) -> Vec<&str> {
+        changed_langs(events, &self.dictionaries)
+    }
+
+    /// Languages whose templates finished loading or changed on disk this frame.
+    pub(super) fn changed_templates(
+        &self,
+        events: &mut EventReader<AssetEvent<TemplatesAsset>>,
+    ) -> Vec<&str> {
+        changed_langs(events, &self.templates)
+    }
+}
+
+fn changed_langs<'a, A: Asset>(
+    events: &mut EventReader<AssetEvent<A>>,
+    handles: &'a HashMap<String, Handle<A>>,
+) -> Vec<&'a str> {
+    let mut changed = Vec::new();
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event
+        else {
+            continue;
+        };
+        if let Some((lang, _)) = handles.iter().find(|(_, handle)| handle.id() == *id)
+            && !changed.contains(&lang.as_str())
+        {
+            changed.push(lang.as_str());
+        }
+    }
+    changed
+}
+
+/// Requests the dictionaries and templates of every available language not requested yet.
+pub(super) fn load_text_generator_files(
+    asset_server: Res<AssetServer>,
+    available: Res<AvailableLanguages>,
+    mut files: ResMut<TextGeneratorFiles>,
+) {
+    for language in available.iter() {
+        let path = |kind: LocaleFileKind| {
+            format!(
+                "{}/{}/{}",
+                LOCALES_ASSET_PATH,
+                language.code,
+                kind.file_name()
+            )
+        };
+        if language.has_dictionaries && !files.dictionaries.contains_key(&language.code) {
+            let handle = asset_server.load(path(LocaleFileKind::Dictionaries));
+            files.dictionaries.insert(language.code.clone(), handle);
+        }
+        if language.has_templates && !files.templates.contains_key(&language.code) {
+            let handle = asset_server.load(path(LocaleFileKind::Templates));
+            files.templates.insert(language.code.clone(), handle);
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+
+/// Values for the `{$NAME}` slots of a template, e.g. the victim and weapon of
+/// a match event: `TextContext::new().with("victim", "Sgt. Parsnip")`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextContext {
+    values: HashMap<String, String>,
+}
+
+impl TextContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Sets or replaces a value.
+    pub fn set(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.values.insert(name.into(), value.into());
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+}
@@ -0,0 +1,42 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use super::validation::ValidationReport;
+
+/// Why a [`super::WhackaMoleeGenerator`] could not be built.
+#[derive(Debug)]
+pub enum TextGeneratorError {
+    /// A locale file could not be read.
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// A locale file is not valid JSON of the expected shape, or has a
+    /// template that cannot be parsed.
+    Parse {
+        file: &'static str,
+        source: serde_json::Error,
+    },
+    /// The files parsed, but would generate broken text.
+    Invalid(ValidationReport),
+}
+
+impl fmt::Display for TextGeneratorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "cannot read {}: {}", path.display(), source),
+            Self::Parse { file, source } => write!(f, "cannot parse {}: {}", file, source),
+            Self::Invalid(report) => write!(f, "{}", report),
+        }
+    }
+}
+
+impl std::error::Error for TextGeneratorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Parse { source, .. } => Some(source),
+            Self::Invalid(_) => None,
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use bevy::log::warn;
+use rand::Rng;
+use rand::seq::IndexedRandom;
+use std::fmt;
+
+use super::template::Template;
+use super::{TextContext, WhackaMoleeGenerator};
+
+/// Texts drawn before a limited generation gives up. In the second half,
+/// words are capped at half as many characters as there are draws left, so
+/// ever shorter words are tried.
+const FIT_ATTEMPTS: usize = 64;
+
+/// How long a generated text may be.
+#[derive(Clone, Copy)]
+pub enum TextLimit<'a> {
+    /// At most this many characters.
+    Chars(usize),
+    /// Whatever the function accepts, e.g. a check of the rendered width
+    /// against a name plate.
+    Fits(&'a dyn Fn(&str) -> bool),
+}
+
+impl TextLimit<'_> {
+    fn accepts(&self, text: &str) -> bool {
+        match self {
+            Self::Chars(max) => text.chars().count() <= *max,
+            Self::Fits(fits) => fits(text),
+        }
+    }
+}
+
+/// No text of `category` could be made to fit the limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextDoesNotFit {
+    pub category: String,
+}
+
+impl fmt::Display for TextDoesNotFit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no {} text fits the limit", self.category)
+    }
+}
+
+impl std::error::Error for TextDoesNotFit {}
+
+impl WhackaMoleeGenerator {
+    /// The limit `templates.json` sets for `category` under `MAX_LENGTHS`, in characters.
+    pub fn max_length(&self, category: &str) -> Option<usize> {
+        self.templates.max_length(category)
+    }
+
+    /// Text of `category` within its `MAX_LENGTHS` limit, or any text when it has none.
+    pub fn generate_fitting(&self, category: &str) -> Result<String, TextDoesNotFit> {
+        self.generate_fitting_with_rng(category, &mut rand::rng())
+    }
+
+    /// Like [`Self::generate_fitting`], drawing every choice from `rng`.
+    pub fn generate_fitting_with_rng<R: Rng + ?Sized>(
+        &self,
+        category: &str,
+        rng: &mut R,
+    ) -> Result<String, TextDoesNotFit> {
+        match self.max_length(category) {
+            Some(max) => self.generate_limited_with_rng(category, TextLimit::Chars(max), rng),
+            None => Ok(self.generate_with_rng(category, rng)),
+        }
+    }
+
+    /// Text of `category` within its `MAX_LENGTHS` limit, or, when none
+    /// fits, a text of any length rather than none.
+    pub(super) fn generate_fitting_or_any_with_rng<R: Rng + ?Sized>(
+        &self,
+        category: &str,
+        rng: &mut R,
+    ) -> String {
+        self.generate_fitting_with_rng(category, rng)
+            .unwrap_or_else(|e| {
+                warn!("TextGen: {}; using a longer text.", e);
+                self.generate_with_rng(category, rng)
+            })
+    }
+
+    /// Text of `category` within `limit`. Texts are drawn again until one
+    /// fits, with shorter words after a while. Templates too long for a
+    /// [`TextLimit::Chars`] even with their shortest words are never used.
+    pub fn generate_limited(
+        &self,
+        category: &str,
+        limit: TextLimit,
+    ) -> Result<String, TextDoesNotFit> {
+        self.generate_limited_with_rng(category, limit, &mut rand::rng())
+    }
+
+    /// Like [`Self::generate_limited`], drawing every choice from `rng`.
+    pub fn generate_limited_with_rng<R: Rng + ?Sized>(
+        &self,
+        category: &str,
+        limit: TextLimit,
+        rng: &mut R,
+    ) -> Result<String, TextDoesNotFit> {
+        self.generate_limited_with_context_and_rng(category, &TextContext::default(), limit, rng)
+    }
+
+    /// Like [`Self::generate_limited`], with `{$NAME}` slots filled from `context`.
+    pub fn generate_limited_with_context_and_rng<R: Rng + ?Sized>(
+        &self,
+        category: &str,
+        context: &TextContext,
+        limit: TextLimit,
+        rng: &mut R,
+    ) -> Result<String, TextDoesNotFit> {
+        let does_not_fit = || TextDoesNotFit {
+            category: category.to_string(),
+        };
+        let templates: Vec<&Template> = self
+            .templates
+            .get(category)
+            .into_iter()
+            .flatten()
+            .filter(|template| match limit {
+                TextLimit::Chars(max) => self
+                    .length_range(template, Some(context))
+                    .is_some_and(|lengths| *lengths.start() <= max),
+                TextLimit::Fits(_) => true,
+            })
+            .collect();
+
+        for attempt in 0..FIT_ATTEMPTS {
+            let template = templates.choose(rng).ok_or_else(does_not_fit)?;
+            let word_limit = (attempt >= FIT_ATTEMPTS / 2).then_some((FIT_ATTEMPTS - attempt) / 2);
+            let text = self.process_template(template, context, word_limit, rng);
+            if limit.accepts(&text) {
+                return Ok(text);
+            }
+        }
+        Err(does_not_fit())
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::{BTreeSet, HashSet};
+
+use super::WhackaMoleeGenerator;
+use super::assets::{DictionariesAsset, TemplatesAsset};
+use super::grammar::{Agreement, Case, Gender};
+use super::template::{Placeholder, Segment, Source, Template};
+use crate::localization::pseudo;
+
+/// Characters text generated from a language's files can contain, see
+/// [`WhackaMoleeGenerator::glyphs`]. The files are not validated, and noun
+/// forms missing from them are not logged; generating text logs those.
+pub fn generated_glyphs(
+    lang: &str,
+    dictionaries: &DictionariesAsset,
+    templates: &TemplatesAsset,
+    reference_dictionaries: Option<&DictionariesAsset>,
+    pseudolocalized: bool,
+) -> BTreeSet<char> {
+    let generator = WhackaMoleeGenerator::unvalidated(
+        lang,
+        dictionaries.0.clone(),
+        templates.0.clone(),
+        reference_dictionaries.map(|reference| reference.0.clone()),
+    );
+    let generator = if pseudolocalized {
+        generator.pseudolocalized()
+    } else {
+        generator
+    };
+    generator.glyphs()
+}
+
+impl WhackaMoleeGenerator {
+    /// Every character generated text can contain: the literal text of the
+    /// templates, and every word each slot can draw, in the forms the slot asks
+    /// for. `{$NAME}` values come from the game and are left out. Missing noun
+    /// forms are not logged.
+    pub fn glyphs(&self) -> BTreeSet<char> {
+        let mut texts: Vec<String> = Vec::new();
+        // Slots asking for the same words in the same way render the same texts.
+        let mut rendered: HashSet<(String, String)> = HashSet::new();
+        for (_, templates) in self.templates.categories() {
+            for template in templates {
+                for segment in template.segments() {
+                    match segment {
+                        Segment::Literal(range) => texts.push(template.text(range).to_string()),
+                        Segment::Slot(placeholder) => {
+                            let Some(drawn_by) = drawing_slot(template, placeholder) else {
+                                continue;
+                            };
+                            let Some(key) = drawn_by.dictionary_key() else {
+                                continue;
+                            };
+                            let signature =
+                                format!("{:?}|{:?}", drawn_by.tags, modifiers(placeholder));
+                            if rendered.insert((key.to_string(), signature)) {
+                                texts.extend(self.slot_texts(placeholder, key, &drawn_by.tags));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut glyphs: BTreeSet<char> = if self.pseudolocalized {
+            texts
+                .iter()
+                .flat_map(|text| pseudo::pseudolocalize(text).chars().collect::<Vec<_>>())
+                .collect()
+        } else {
+            texts.iter().flat_map(|text| text.chars()).collect()
+        };
+        glyphs.retain(|c| !c.is_whitespace() && !c.is_control());
+        glyphs
+    }
+
+    /// Every way `placeholder` can render the words of `key` carrying `tags`.
+    fn slot_texts(&self, placeholder: &Placeholder, key: &str, tags: &[String]) -> Vec<String> {
+        let Some(entries) = self.entries_tagged(key, tags) else {
+            return Vec::new();
+        };
+        let agreements: Vec<Option<Agreement>> = if placeholder.agree.is_some() {
+            Gender::ALL
+                .into_iter()
+                .flat_map(|gender| {
+                    [false, true].into_iter().flat_map(move |plural| {
+                        Case::ALL.into_iter().map(move |case| {
+                            Some(Agreement {
+                                gender,
+                                plural,
+                                case,
+                            })
+                        })
+                    })
+                })
+                .collect()
+        } else {
+            vec![None]
+        };
+        entries
+            .flat_map(|entry| {
+                agreements
+                    .iter()
+                    .map(move |agreement| self.render(placeholder, key, entry, *agreement, true))
+            })
+            .collect()
+    }
+}
+
+/// The slot whose word `placeholder` shows: itself, or the one it names.
+/// `None` for `{$NAME}` slots.
+fn drawing_slot<'a>(
+    template: &'a Template,
+    placeholder: &'a Placeholder,
+) -> Option<&'a Placeholder> {
+    match &placeholder.source {
+        Source::Dictionary(_) => Some(placeholder),
+        Source::Reference(name) => template
+            .slots()
+            .find(|slot| slot.name.as_deref() == Some(name.as_str())),
+        Source::Context(_) => None,
+    }
+}
+
+fn modifiers(placeholder: &Placeholder) -> (bool, Option<Gender>, Option<Case>, bool, bool, bool) {
+    (
+        placeholder.plural,
+        placeholder.gender,
+        placeholder.case,
+        placeholder.agree.is_some(),
+        placeholder.capitalize,
+        placeholder.article,
+    )
+}
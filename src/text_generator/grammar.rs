@@ -0,0 +1,976 @@
+use serde::{Deserialize, Serialize};
+
+/// Grammatical gender of a noun. Polish tells apart masculine nouns naming
+/// people, since adjectives take other plural endings with them.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Gender {
+    #[default]
+    #[serde(rename = "m")]
+    Masculine,
+    #[serde(rename = "mp")]
+    MasculinePersonal,
+    #[serde(rename = "f")]
+    Feminine,
+    #[serde(rename = "n")]
+    Neuter,
+}
+
+impl Gender {
+    pub const ALL: [Gender; 4] = [
+        Self::Masculine,
+        Self::MasculinePersonal,
+        Self::Feminine,
+        Self::Neuter,
+    ];
+
+    /// Name used in dictionaries and templates.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Self::Masculine => "m",
+            Self::MasculinePersonal => "mp",
+            Self::Feminine => "f",
+            Self::Neuter => "n",
+        }
+    }
+
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|gender| gender.tag() == tag)
+    }
+}
+
+/// Grammatical case. Only Polish inflects for it; the other languages treat
+/// every case as the nominative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Case {
+    #[default]
+    Nominative,
+    Genitive,
+    Dative,
+    Accusative,
+    Instrumental,
+    Locative,
+}
+
+impl Case {
+    pub const ALL: [Case; 6] = [
+        Self::Nominative,
+        Self::Genitive,
+        Self::Dative,
+        Self::Accusative,
+        Self::Instrumental,
+        Self::Locative,
+    ];
+
+    /// Name used in dictionaries and templates.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Self::Nominative => "nom",
+            Self::Genitive => "gen",
+            Self::Dative => "dat",
+            Self::Accusative => "acc",
+            Self::Instrumental => "ins",
+            Self::Locative => "loc",
+        }
+    }
+
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|case| case.tag() == tag)
+    }
+}
+
+/// Gender, number and case a word takes to agree with its noun.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Agreement {
+    pub gender: Gender,
+    pub plural: bool,
+    pub case: Case,
+}
+
+impl Agreement {
+    /// Name of this form in a dictionary entry's `forms`: the case, gender and
+    /// number joined by dots, each left out when it is the default, e.g. "f",
+    /// "mp.pl" or "gen.f". Nouns have a fixed gender, so their forms leave it
+    /// out ("gen", "gen.pl").
+    pub fn form_key(&self, with_gender: bool) -> String {
+        let mut parts = Vec::new();
+        if self.case != Case::Nominative {
+            parts.push(self.case.tag());
+        }
+        if with_gender && self.gender != Gender::Masculine {
+            parts.push(self.gender.tag());
+        }
+        if self.plural {
+            parts.push("pl");
+        }
+        parts.join(".")
+    }
+}
+
+/// How a language inflects dictionary entries that do not spell out the form
+/// a template needs in `dictionaries.json`.
+pub trait GrammarRules: Send + Sync + std::fmt::Debug {
+    /// Nominative plural of a noun phrase.
+    fn pluralize(&self, phrase: &str) -> String;
+
+    /// Gender of a noun phrase that has none in the dictionary.
+    fn guess_gender(&self, _phrase: &str) -> Gender {
+        Gender::Masculine
+    }
+
+    /// A noun phrase, given in the nominative of the wanted number, in `case`.
+    /// `None` when the rules cannot tell and the dictionary has to give the form.
+    fn decline(
+        &self,
+        nominative: &str,
+        _gender: Gender,
+        _plural: bool,
+        _case: Case,
+    ) -> Option<String> {
+        Some(nominative.to_string())
+    }
+
+    /// An adjective phrase, given in the masculine singular nominative, in the
+    /// form agreeing with `agreement`.
+    fn inflect_adjective(&self, phrase: &str, _agreement: Agreement) -> String {
+        phrase.to_string()
+    }
+
+    /// `phrase` after the indefinite article agreeing with `agreement`, for
+    /// languages that have one. The phrase is already inflected.
+    fn with_indefinite_article(&self, phrase: &str, _agreement: Agreement) -> String {
+        phrase.to_string()
+    }
+}
+
+/// Rules for `lang`, English for languages without rules of their own.
+pub fn grammar_rules_for(lang: &str) -> &'static dyn GrammarRules {
+    match lang {
+        "es" => &Spanish,
+        "pl" => &Polish,
+        _ => &English,
+    }
+}
+
+/// Suffix rules applied to the end of the phrase, so "siege tower" becomes "siege towers".
+/// English adjectives and nouns do not agree, so nothing else changes.
+#[derive(Debug)]
+pub struct English;
+
+impl GrammarRules for English {
+    fn pluralize(&self, word: &str) -> String {
+        if word.ends_with('y')
+            && !["ay", "ey", "iy", "oy", "uy"]
+                .iter()
+                .any(|s| word.ends_with(s))
+        {
+            format!("{}ies", &word[0..word.len() - 1])
+        } else if word.ends_with("ch")
+            || word.ends_with('s')
+            || word.ends_with("sh")
+            || word.ends_with('x')
+            || word.ends_with('z')
+        {
+            format!("{}es", word)
+        } else {
+            format!("{}s", word)
+        }
+    }
+
+    /// "a" or "an" by the sound the phrase starts with; plurals take no article.
+    fn with_indefinite_article(&self, phrase: &str, agreement: Agreement) -> String {
+        if agreement.plural {
+            return phrase.to_string();
+        }
+        let lower = phrase.to_lowercase();
+        let starts_with_any =
+            |prefixes: &[&str]| prefixes.iter().any(|prefix| lower.starts_with(prefix));
+        let vowel_sound = if starts_with_any(&ENGLISH_VOWEL_SOUNDING_CONSONANTS) {
+            true
+        } else if starts_with_any(&ENGLISH_CONSONANT_SOUNDING_VOWELS) {
+            false
+        } else {
+            lower.starts_with(['a', 'e', 'i', 'o', 'u'])
+        };
+        format!("{} {}", if vowel_sound { "an" } else { "a" }, phrase)
+    }
+}
+
+/// Beginnings read with a vowel sound despite the letter: "an hour".
+const ENGLISH_VOWEL_SOUNDING_CONSONANTS: [&str; 4] = ["heir", "honest", "honor", "hour"];
+/// Beginnings read with a consonant sound despite the letter: "a unicorn", "a one-off".
+const ENGLISH_CONSONANT_SOUNDING_VOWELS: [&str; 7] =
+    ["eu", "one", "once", "uni", "use", "usu", "uto"];
+
+/// Spanish rules. Every word up to the first preposition is pluralized, since
+/// adjectives agree with their noun: "coche blindado" becomes "coches blindados"
+/// while "torre de asedio" becomes "torres de asedio". Adjectives agree in gender
+/// through their first word, so "curtido en batalla" becomes "curtida en batalla".
+#[derive(Debug)]
+pub struct Spanish;
+
+const SPANISH_PREPOSITIONS: [&str; 6] = ["a", "con", "de", "del", "en", "para"];
+/// Endings of feminine nouns that do not end in "-a".
+const SPANISH_FEMININE_ENDINGS: [&str; 5] = ["a", "ión", "dad", "tad", "tud"];
+/// Final consonants of native words; adjectives ending in any other one are
+/// loanwords ("premium", "gourmet") and keep their form in the plural.
+const SPANISH_NATIVE_FINAL_CONSONANTS: &str = "dlnrsxzj";
+
+impl Spanish {
+    fn pluralize_word(word: &str) -> String {
+        let Some(last) = word.chars().last() else {
+            return String::new();
+        };
+        match last {
+            'a' | 'e' | 'i' | 'o' | 'u' | 'á' | 'é' | 'ó' => format!("{}s", word),
+            'í' | 'ú' => format!("{}es", word),
+            'z' => format!("{}ces", &word[..word.len() - 1]),
+            // "lunes" and "tórax" keep their form unless stressed on the last syllable.
+            's' | 'x' if !last_vowel_is_accented(word) => word.to_string(),
+            'n' | 's' => format!("{}es", remove_last_accent(word)),
+            _ => format!("{}es", word),
+        }
+    }
+
+    fn inflect_adjective_word(word: &str, agreement: Agreement) -> String {
+        let feminine = agreement.gender == Gender::Feminine;
+        // "-o" and "-or" adjectives have a feminine form; "-e" and the other ones do not.
+        let base = match word.strip_suffix('o') {
+            Some(stem) if feminine => format!("{}a", stem),
+            _ if feminine && word.ends_with("or") => format!("{}a", word),
+            _ => word.to_string(),
+        };
+        let Some(last) = base.chars().last() else {
+            return base;
+        };
+        if !agreement.plural || !(is_vowel(last) || SPANISH_NATIVE_FINAL_CONSONANTS.contains(last))
+        {
+            base
+        } else {
+            Self::pluralize_word(&base)
+        }
+    }
+}
+
+impl GrammarRules for Spanish {
+    fn pluralize(&self, phrase: &str) -> String {
+        let mut in_head = true;
+        phrase
+            .split(' ')
+            .map(|word| {
+                if SPANISH_PREPOSITIONS.contains(&word) {
+                    in_head = false;
+                }
+                if in_head {
+                    Self::pluralize_word(word)
+                } else {
+                    word.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Feminine for nouns ending in "-a", "-ión" or "-dad", masculine otherwise.
+    fn guess_gender(&self, phrase: &str) -> Gender {
+        let head = phrase.split(' ').next().unwrap_or(phrase).to_lowercase();
+        if SPANISH_FEMININE_ENDINGS
+            .iter()
+            .any(|ending| head.ends_with(ending))
+        {
+            Gender::Feminine
+        } else {
+            Gender::Masculine
+        }
+    }
+
+    fn inflect_adjective(&self, phrase: &str, agreement: Agreement) -> String {
+        match phrase.split_once(' ') {
+            // "de primera" is a prepositional phrase and never changes.
+            Some((first, _)) if SPANISH_PREPOSITIONS.contains(&first) => phrase.to_string(),
+            Some((first, rest)) => format!(
+                "{} {}",
+                Self::inflect_adjective_word(first, agreement),
+                rest
+            ),
+            None => Self::inflect_adjective_word(phrase, agreement),
+        }
+    }
+
+    fn with_indefinite_article(&self, phrase: &str, agreement: Agreement) -> String {
+        let article = match (agreement.gender, agreement.plural) {
+            (Gender::Feminine, false) => "una",
+            (Gender::Feminine, true) => "unas",
+            (_, false) => "un",
+            (_, true) => "unos",
+        };
+        format!("{} {}", article, phrase)
+    }
+}
+
+const ACCENTED_VOWELS: [(char, char); 5] =
+    [('á', 'a'), ('é', 'e'), ('í', 'i'), ('ó', 'o'), ('ú', 'u')];
+
+fn is_vowel(c: char) -> bool {
+    "aeiouáéíóúü".contains(c)
+}
+
+fn last_vowel_is_accented(word: &str) -> bool {
+    word.chars()
+        .rev()
+        .find(|c| is_vowel(*c))
+        .is_some_and(|c| ACCENTED_VOWELS.iter().any(|(accented, _)| *accented == c))
+}
+
+/// Drops the written accent of the last vowel: adding a syllable moves the
+/// stress rule so "camión" becomes "camiones".
+fn remove_last_accent(word: &str) -> String {
+    let Some((index, vowel)) = word.char_indices().rev().find(|(_, c)| is_vowel(*c)) else {
+        return word.to_string();
+    };
+    match ACCENTED_VOWELS
+        .iter()
+        .find(|(accented, _)| *accented == vowel)
+    {
+        Some((_, plain)) => format!(
+            "{}{}{}",
+            &word[..index],
+            plain,
+            &word[index + vowel.len_utf8()..]
+        ),
+        None => word.to_string(),
+    }
+}
+
+/// Polish rules. The first noun of a phrase and the adjectives around it are
+/// inflected; nouns in the genitive after it ("linia frontu") and everything
+/// after a preposition are left alone. Only masculine adjectives ("długi łuk")
+/// are recognized before the noun, since feminine nouns share their endings.
+///
+/// Plurals cover inanimate and feminine nouns; masculine personal nouns
+/// ("generał" -> "generałowie") and nouns with a mobile "e" in a stressed
+/// syllable need explicit plurals. Of the other cases only the singular
+/// genitive and accusative are derived; masculine nouns take "-a" in the
+/// genitive after "-k", "-er", "-or" and hushing consonants and "-u" otherwise,
+/// so the exceptions ("szpinak" -> "szpinaku") need explicit forms.
+/// Adjectives are inflected in every case, number and gender.
+#[derive(Debug)]
+pub struct Polish;
+
+const POLISH_PREPOSITIONS: [&str; 7] = ["do", "na", "od", "w", "z", "ze", "dla"];
+const POLISH_ADJECTIVE_ENDINGS: [&str; 12] = [
+    "owy", "owa", "ny", "na", "ki", "ska", "cka", "gi", "czy", "cza", "ły", "ła",
+];
+/// Final consonants after which plurals take "-e" instead of "-y".
+const POLISH_SOFT_ENDINGS: [&str; 8] = ["cz", "sz", "rz", "ż", "dz", "c", "l", "j"];
+/// Endings of masculine nouns taking "-a" in the genitive ("pomidora", "noża").
+const POLISH_GENITIVE_A_ENDINGS: [&str; 7] = ["k", "er", "or", "cz", "sz", "rz", "ż"];
+/// Stem endings of the masculine personal plural of adjectives, most specific first.
+const POLISH_PERSONAL_PLURAL_ENDINGS: [(&str, &str); 16] = [
+    ("sz", "si"),
+    ("cz", "czy"),
+    ("rz", "rzy"),
+    ("ż", "ży"),
+    ("dz", "dzy"),
+    ("c", "cy"),
+    ("ch", "si"),
+    ("st", "ści"),
+    ("sn", "śni"),
+    // Participles: "zmiażdżony" -> "zmiażdżeni".
+    ("on", "eni"),
+    ("r", "rzy"),
+    ("ł", "li"),
+    ("t", "ci"),
+    ("d", "dzi"),
+    ("s", "si"),
+    ("z", "zi"),
+];
+
+impl Polish {
+    fn is_adjective(word: &str) -> bool {
+        POLISH_ADJECTIVE_ENDINGS
+            .iter()
+            .any(|ending| word.ends_with(ending))
+    }
+
+    /// Whether `word` is the noun of the phrase rather than an adjective before it.
+    fn is_head_noun(word: &str) -> bool {
+        !(Self::is_adjective(word) && word.ends_with(['y', 'i']))
+    }
+
+    fn pluralize_adjective(word: &str) -> String {
+        let stem = &word[..word.len() - 1];
+        if stem.ends_with('k') || stem.ends_with('g') {
+            format!("{}ie", stem)
+        } else {
+            format!("{}e", stem)
+        }
+    }
+
+    fn pluralize_noun(word: &str) -> String {
+        let mut chars: Vec<char> = word.chars().collect();
+        let Some(&last) = chars.last() else {
+            return String::new();
+        };
+        match last {
+            'a' => {
+                let stem = &word[..word.len() - 1];
+                if stem.ends_with('k') || stem.ends_with('g') {
+                    format!("{}i", stem)
+                } else if stem.ends_with('i')
+                    || POLISH_SOFT_ENDINGS.iter().any(|soft| stem.ends_with(soft))
+                {
+                    format!("{}e", stem)
+                } else {
+                    format!("{}y", stem)
+                }
+            }
+            'o' | 'e' => format!("{}a", &word[..word.len() - 1]),
+            // Plural-only nouns ("chipsy") and loanwords ("chili", "purée") keep their form.
+            'i' | 'y' | 'é' | 'u' => word.to_string(),
+            'ń' => format!("{}nie", &word[..word.len() - 'ń'.len_utf8()]),
+            'ś' => format!("{}sie", &word[..word.len() - 'ś'.len_utf8()]),
+            'ć' => format!("{}cie", &word[..word.len() - 'ć'.len_utf8()]),
+            'ź' => format!("{}zie", &word[..word.len() - 'ź'.len_utf8()]),
+            _ => {
+                // The "ó" of a closed final syllable opens up: "wóz" -> "wozy".
+                let len = chars.len();
+                if len >= 2 && chars[len - 2] == 'ó' {
+                    chars[len - 2] = 'o';
+                }
+                let word: String = chars.into_iter().collect();
+                if let Some(stem) = word.strip_suffix("iec") {
+                    format!("{}ce", stem)
+                } else if let Some(stem) = word.strip_suffix("ec") {
+                    format!("{}ce", stem)
+                } else if let Some(stem) = word.strip_suffix("ek") {
+                    format!("{}ki", stem)
+                } else if word.ends_with('k') || word.ends_with('g') {
+                    format!("{}i", word)
+                } else if POLISH_SOFT_ENDINGS.iter().any(|soft| word.ends_with(soft)) {
+                    format!("{}e", word)
+                } else {
+                    format!("{}y", word)
+                }
+            }
+        }
+    }
+
+    /// Genitive singular of a single noun.
+    fn genitive(word: &str, gender: Gender) -> Option<String> {
+        if let Some(stem) = word.strip_suffix('a') {
+            return Some(Self::feminine_genitive(stem));
+        }
+        if word.ends_with(['y', 'i']) {
+            // Adjectival nouns ("szeregowy") decline like adjectives; loanwords ("chili") do not.
+            return Some(match gender {
+                Gender::Masculine | Gender::MasculinePersonal => Self::inflect_adjective_word(
+                    word,
+                    Agreement {
+                        gender,
+                        plural: false,
+                        case: Case::Genitive,
+                    },
+                ),
+                _ => word.to_string(),
+            });
+        }
+        if let Some(stem) = word.strip_suffix(['o', 'e']) {
+            return Some(format!("{}a", stem));
+        }
+        if word.ends_with("um") || word.ends_with(['é', 'u']) {
+            return Some(word.to_string());
+        }
+        match gender {
+            Gender::Feminine if POLISH_SOFT_ENDINGS.iter().any(|soft| word.ends_with(soft)) => {
+                Some(format!("{}y", word))
+            }
+            Gender::Feminine => Some(format!("{}i", word)),
+            Gender::Neuter => None,
+            Gender::Masculine | Gender::MasculinePersonal => {
+                Some(Self::masculine_genitive(word, gender))
+            }
+        }
+    }
+
+    fn feminine_genitive(stem: &str) -> String {
+        if stem.ends_with('k') || stem.ends_with('g') || stem.ends_with('l') || stem.ends_with('j')
+        {
+            return format!("{}i", stem);
+        }
+        if let Some(before) = stem.strip_suffix("ni") {
+            // Loanwords keep their "i": "linia" -> "linii", but "dynia" -> "dyni".
+            return if before.ends_with(['i', 'a']) {
+                format!("{}i", stem)
+            } else {
+                stem.to_string()
+            };
+        }
+        if ["ci", "si", "zi"].iter().any(|soft| stem.ends_with(soft)) {
+            return stem.to_string();
+        }
+        if stem.ends_with('i') {
+            return format!("{}i", stem);
+        }
+        format!("{}y", stem)
+    }
+
+    fn masculine_genitive(word: &str, gender: Gender) -> String {
+        let mut chars: Vec<char> = word.chars().collect();
+        let len = chars.len();
+        if len >= 2 && chars[len - 2] == 'ó' {
+            chars[len - 2] = 'o';
+        }
+        let word: String = chars.into_iter().collect();
+        if let Some(stem) = word.strip_suffix("iec").or_else(|| word.strip_suffix("ec")) {
+            return format!("{}ca", stem);
+        }
+        if let Some(stem) = word.strip_suffix("ek") {
+            return format!("{}ka", stem);
+        }
+        let takes_a = gender == Gender::MasculinePersonal
+            || POLISH_GENITIVE_A_ENDINGS
+                .iter()
+                .any(|ending| word.ends_with(ending));
+        format!("{}{}", word, if takes_a { "a" } else { "u" })
+    }
+
+    fn decline_noun(word: &str, gender: Gender, case: Case) -> Option<String> {
+        match (case, gender) {
+            (Case::Nominative, _) => Some(word.to_string()),
+            (Case::Genitive, _) | (Case::Accusative, Gender::MasculinePersonal) => {
+                Self::genitive(word, gender)
+            }
+            (Case::Accusative, Gender::Feminine) => Some(match word.strip_suffix('a') {
+                Some(stem) => format!("{}ę", stem),
+                None => word.to_string(),
+            }),
+            (Case::Accusative, _) => Some(word.to_string()),
+            _ => None,
+        }
+    }
+
+    fn inflect_adjective_word(word: &str, agreement: Agreement) -> String {
+        let (stem, soft) = if let Some(stem) = word.strip_suffix('y') {
+            (stem, false)
+        } else if let Some(stem) = word.strip_suffix('i') {
+            (stem, true)
+        } else {
+            return word.to_string();
+        };
+        // "słodki" and "tani" are both soft, but only the latter keeps its "i" before "a".
+        let i_before_a = soft && !stem.ends_with(['k', 'g']);
+        let y = if soft { "i" } else { "y" };
+        let e = if soft { "ie" } else { "e" };
+        let a = if i_before_a { "ia" } else { "a" };
+        let a_nasal = if i_before_a { "ią" } else { "ą" };
+        let personal = agreement.gender == Gender::MasculinePersonal;
+        let ending = match (agreement.plural, agreement.gender, agreement.case) {
+            (false, Gender::Feminine, Case::Nominative) => a.to_string(),
+            (false, Gender::Feminine, Case::Accusative | Case::Instrumental) => a_nasal.to_string(),
+            (false, Gender::Feminine, _) => format!("{}j", e),
+            (false, Gender::Neuter, Case::Nominative | Case::Accusative) => e.to_string(),
+            (false, _, Case::Nominative) => y.to_string(),
+            (false, _, Case::Accusative) if !personal => y.to_string(),
+            (false, _, Case::Genitive | Case::Accusative) => format!("{}go", e),
+            (false, _, Case::Dative) => format!("{}mu", e),
+            (false, _, Case::Instrumental | Case::Locative) => format!("{}m", y),
+            (true, _, Case::Nominative) if personal => return Self::personal_plural(stem, soft),
+            (true, _, Case::Nominative) => e.to_string(),
+            (true, _, Case::Accusative) if !personal => e.to_string(),
+            (true, _, Case::Genitive | Case::Accusative | Case::Locative) => format!("{}ch", y),
+            (true, _, Case::Dative) => format!("{}m", y),
+            (true, _, Case::Instrumental) => format!("{}mi", y),
+        };
+        format!("{}{}", stem, ending)
+    }
+
+    /// Masculine singular nominative of an adjective that follows its noun in
+    /// another gender: "zadaniowa" -> "zadaniowy".
+    fn masculine_form(word: &str) -> String {
+        match word.strip_suffix(['a', 'e']) {
+            Some(stem) if stem.ends_with(['k', 'g']) => format!("{}i", stem),
+            Some(stem) => match stem.strip_suffix('i') {
+                Some(soft_stem) => format!("{}i", soft_stem),
+                None => format!("{}y", stem),
+            },
+            None => word.to_string(),
+        }
+    }
+
+    /// Nominative plural of an adjective describing people, which softens the
+    /// stem: "ostry" -> "ostrzy", "słodki" -> "słodcy".
+    fn personal_plural(stem: &str, soft: bool) -> String {
+        if soft {
+            return if let Some(base) = stem.strip_suffix('k') {
+                format!("{}cy", base)
+            } else if let Some(base) = stem.strip_suffix('g') {
+                format!("{}dzy", base)
+            } else {
+                format!("{}i", stem)
+            };
+        }
+        for (ending, replacement) in POLISH_PERSONAL_PLURAL_ENDINGS {
+            if let Some(base) = stem.strip_suffix(ending) {
+                return format!("{}{}", base, replacement);
+            }
+        }
+        format!("{}i", stem)
+    }
+}
+
+impl GrammarRules for Polish {
+    fn pluralize(&self, phrase: &str) -> String {
+        let words: Vec<&str> = phrase.split(' ').collect();
+        if words.len() == 1 {
+            return Self::pluralize_noun(phrase);
+        }
+        let mut seen_noun = false;
+        let mut in_head = true;
+        words
+            .iter()
+            .map(|word| {
+                if POLISH_PREPOSITIONS.contains(word) {
+                    in_head = false;
+                }
+                if !in_head {
+                    word.to_string()
+                } else if Self::is_adjective(word) && (seen_noun || word.ends_with(['y', 'i'])) {
+                    Self::pluralize_adjective(word)
+                } else if !seen_noun {
+                    seen_noun = true;
+                    Self::pluralize_noun(word)
+                } else {
+                    word.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Feminine for nouns ending in "-a", neuter for "-o", "-e" and "-um",
+    /// masculine otherwise.
+    fn guess_gender(&self, phrase: &str) -> Gender {
+        let head = phrase
+            .split(' ')
+            .find(|word| Self::is_head_noun(word))
+            .unwrap_or(phrase);
+        if head.ends_with('a') {
+            Gender::Feminine
+        } else if head.ends_with(['o', 'e', 'ę']) || head.ends_with("um") {
+            Gender::Neuter
+        } else {
+            Gender::Masculine
+        }
+    }
+
+    fn decline(
+        &self,
+        nominative: &str,
+        gender: Gender,
+        plural: bool,
+        case: Case,
+    ) -> Option<String> {
+        if plural {
+            // The plural accusative matches the nominative except for people.
+            let same_as_nominative = case == Case::Nominative
+                || (case == Case::Accusative && gender != Gender::MasculinePersonal);
+            return same_as_nominative.then(|| nominative.to_string());
+        }
+        let agreement = Agreement {
+            gender,
+            plural,
+            case,
+        };
+        let mut seen_noun = false;
+        let mut in_head = true;
+        let mut words = Vec::new();
+        for word in nominative.split(' ') {
+            if POLISH_PREPOSITIONS.contains(&word) {
+                in_head = false;
+            }
+            if !in_head {
+                words.push(word.to_string());
+            } else if Self::is_adjective(word) && (seen_noun || word.ends_with(['y', 'i'])) {
+                words.push(Self::inflect_adjective_word(
+                    &Self::masculine_form(word),
+                    agreement,
+                ));
+            } else if !seen_noun {
+                seen_noun = true;
+                words.push(Self::decline_noun(word, gender, case)?);
+            } else {
+                words.push(word.to_string());
+            }
+        }
+        Some(words.join(" "))
+    }
+
+    /// Inflects every adjective of the phrase up to the first preposition, so
+    /// "zaprawiony w boju" becomes "zaprawiona w boju"; adverbs ("ręcznie
+    /// robiony") and indeclinable words ("premium") are kept.
+    fn inflect_adjective(&self, phrase: &str, agreement: Agreement) -> String {
+        let mut in_head = true;
+        phrase
+            .split(' ')
+            .map(|word| {
+                if POLISH_PREPOSITIONS.contains(&word) {
+                    in_head = false;
+                }
+                if in_head {
+                    Self::inflect_adjective_word(word, agreement)
+                } else {
+                    word.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plurals(lang: &str, words: &[&str]) -> Vec<String> {
+        words
+            .iter()
+            .map(|word| grammar_rules_for(lang).pluralize(word))
+            .collect()
+    }
+
+    fn agreeing(lang: &str, adjective: &str, agreements: &[(Gender, bool, Case)]) -> Vec<String> {
+        agreements
+            .iter()
+            .map(|&(gender, plural, case)| {
+                grammar_rules_for(lang).inflect_adjective(
+                    adjective,
+                    Agreement {
+                        gender,
+                        plural,
+                        case,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn genitives(lang: &str, nouns: &[&str]) -> Vec<String> {
+        let rules = grammar_rules_for(lang);
+        nouns
+            .iter()
+            .map(|noun| {
+                rules
+                    .decline(noun, rules.guess_gender(noun), false, Case::Genitive)
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn english_rules_are_the_default() {
+        assert_eq!(
+            plurals("en", &["carrot", "radish", "army", "siege tower"]),
+            ["carrots", "radishes", "armies", "siege towers"]
+        );
+        assert_eq!(plurals("fr", &["carrot"]), ["carrots"]);
+        assert_eq!(
+            agreeing("en", "spicy", &[(Gender::Feminine, true, Case::Genitive)]),
+            ["spicy"]
+        );
+        let singular = Agreement::default();
+        let articles: Vec<String> = ["onion", "carrot", "hour", "unicorn", "honest pea"]
+            .iter()
+            .map(|phrase| English.with_indefinite_article(phrase, singular))
+            .collect();
+        assert_eq!(
+            articles,
+            [
+                "an onion",
+                "a carrot",
+                "an hour",
+                "a unicorn",
+                "an honest pea"
+            ]
+        );
+        assert_eq!(
+            English.with_indefinite_article(
+                "onions",
+                Agreement {
+                    plural: true,
+                    ..singular
+                }
+            ),
+            "onions"
+        );
+    }
+
+    #[test]
+    fn spanish_rules() {
+        assert_eq!(
+            plurals(
+                "es",
+                &[
+                    "zanahoria",
+                    "cañón",
+                    "maíz",
+                    "portaaviones",
+                    "coche blindado",
+                    "torre de asedio"
+                ]
+            ),
+            [
+                "zanahorias",
+                "cañones",
+                "maíces",
+                "portaaviones",
+                "coches blindados",
+                "torres de asedio"
+            ]
+        );
+    }
+
+    #[test]
+    fn spanish_agreement() {
+        let rules = grammar_rules_for("es");
+        assert_eq!(rules.guess_gender("zanahoria"), Gender::Feminine);
+        assert_eq!(rules.guess_gender("legión"), Gender::Feminine);
+        assert_eq!(rules.guess_gender("batallón"), Gender::Masculine);
+        let forms = [
+            (Gender::Masculine, false, Case::Nominative),
+            (Gender::Feminine, false, Case::Nominative),
+            (Gender::Masculine, true, Case::Nominative),
+            (Gender::Feminine, true, Case::Nominative),
+        ];
+        assert_eq!(
+            agreeing("es", "picante", &forms),
+            ["picante", "picante", "picantes", "picantes"]
+        );
+        assert_eq!(
+            agreeing("es", "feroz", &forms),
+            ["feroz", "feroz", "feroces", "feroces"]
+        );
+        assert_eq!(
+            agreeing("es", "devastador", &forms),
+            ["devastador", "devastadora", "devastadores", "devastadoras"]
+        );
+        assert_eq!(
+            agreeing("es", "curtido en batalla", &forms),
+            [
+                "curtido en batalla",
+                "curtida en batalla",
+                "curtidos en batalla",
+                "curtidas en batalla"
+            ]
+        );
+        assert_eq!(
+            agreeing("es", "gourmet", &forms),
+            ["gourmet", "gourmet", "gourmet", "gourmet"]
+        );
+        assert_eq!(agreeing("es", "de primera", &forms[3..]), ["de primera"]);
+    }
+
+    #[test]
+    fn polish_rules() {
+        assert_eq!(
+            plurals(
+                "pl",
+                &[
+                    "marchewka",
+                    "cebula",
+                    "ogórek",
+                    "wóz bojowy",
+                    "linia frontu",
+                    "deska do krojenia"
+                ]
+            ),
+            [
+                "marchewki",
+                "cebule",
+                "ogórki",
+                "wozy bojowe",
+                "linie frontu",
+                "deski do krojenia"
+            ]
+        );
+    }
+
+    #[test]
+    fn polish_agreement() {
+        let rules = grammar_rules_for("pl");
+        assert_eq!(rules.guess_gender("marchewka"), Gender::Feminine);
+        assert_eq!(rules.guess_gender("pole bitwy"), Gender::Neuter);
+        assert_eq!(rules.guess_gender("długi łuk"), Gender::Masculine);
+        assert_eq!(
+            agreeing(
+                "pl",
+                "ostry",
+                &[
+                    (Gender::Feminine, false, Case::Nominative),
+                    (Gender::Neuter, false, Case::Nominative),
+                    (Gender::Feminine, false, Case::Genitive),
+                    (Gender::Masculine, false, Case::Instrumental),
+                    (Gender::Feminine, true, Case::Nominative),
+                    (Gender::MasculinePersonal, true, Case::Nominative),
+                    (Gender::Neuter, true, Case::Genitive),
+                ]
+            ),
+            [
+                "ostra", "ostre", "ostrej", "ostrym", "ostre", "ostrzy", "ostrych"
+            ]
+        );
+        assert_eq!(
+            agreeing(
+                "pl",
+                "słodki",
+                &[
+                    (Gender::Feminine, false, Case::Nominative),
+                    (Gender::Feminine, false, Case::Accusative),
+                    (Gender::Neuter, false, Case::Genitive),
+                    (Gender::MasculinePersonal, true, Case::Nominative),
+                ]
+            ),
+            ["słodka", "słodką", "słodkiego", "słodcy"]
+        );
+        assert_eq!(
+            agreeing(
+                "pl",
+                "zaprawiony w boju",
+                &[(Gender::MasculinePersonal, true, Case::Nominative)]
+            ),
+            ["zaprawieni w boju"]
+        );
+        assert_eq!(
+            agreeing(
+                "pl",
+                "ręcznie robiony",
+                &[(Gender::Feminine, false, Case::Nominative)]
+            ),
+            ["ręcznie robiona"]
+        );
+        assert_eq!(
+            genitives(
+                "pl",
+                &[
+                    "cebula",
+                    "dynia",
+                    "linia frontu",
+                    "pole",
+                    "pomidor",
+                    "ogród",
+                    "ogórek",
+                    "grupa zadaniowa"
+                ]
+            ),
+            [
+                "cebuli",
+                "dyni",
+                "linii frontu",
+                "pola",
+                "pomidora",
+                "ogrodu",
+                "ogórka",
+                "grupy zadaniowej"
+            ]
+        );
+    }
+}
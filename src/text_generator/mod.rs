@@ -0,0 +1,1240 @@
+use crate::localization::pseudo;
+use crate::localization::{
+    AvailableLanguages, CurrentLang, LanguageChangeRequest, LocalizationSystemSet,
+    MissingTranslation, MissingTranslations, PSEUDO_LANG, REFERENCE_LANG,
+};
+use bevy::prelude::*;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use serde_json;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::PathBuf,
+};
+
+pub mod assets;
+mod context;
+mod error;
+mod fitting;
+mod glyphs;
+pub mod grammar;
+mod template;
+mod unique;
+mod validation;
+
+pub use assets::{DictionariesAsset, TemplatesAsset, TextGeneratorFiles};
+pub use context::TextContext;
+pub use error::TextGeneratorError;
+pub use fitting::{TextDoesNotFit, TextLimit};
+pub use glyphs::generated_glyphs;
+use grammar::{Agreement, Case, Gender, GrammarRules, grammar_rules_for};
+use template::{Placeholder, Segment, Source, Template};
+pub use template::{migrate_legacy_template, template_dictionary_keys, template_syntax_error};
+pub use unique::NotEnoughUniqueNames;
+use unique::RecentNames;
+pub use validation::{Severity, ValidationIssue, ValidationReport};
+
+/// One word of a dictionary: either a plain string, or an object that also gives
+/// the forms the language's rules would get wrong, e.g.
+/// `{ "word": "generał", "plural": "generałowie", "gender": "mp" }`.
+/// An entry whose plural is the word itself ("awokado") is indeclinable and
+/// never changes. Objects can also make a word rarer or more common with a
+/// `weight`, and tag it for slots such as `{NOUN_WEAPON[explosive]}`:
+/// `{ "word": "grenade", "tags": ["explosive"], "weight": 0.5 }`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+enum DictionaryEntry {
+    Word(String),
+    Forms {
+        word: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        plural: Option<String>,
+        /// Gender of a noun, guessed from its ending when missing.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gender: Option<Gender>,
+        /// Other inflected forms by [`Agreement::form_key`], e.g. `"gen": "pomidora"`
+        /// for a noun or `"f": "la"` for an article.
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        forms: HashMap<String, String>,
+        /// How often the word is drawn next to the others, which weigh 1.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        weight: Option<f32>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tags: Vec<String>,
+    },
+}
+
+impl DictionaryEntry {
+    fn word(&self) -> &str {
+        match self {
+            Self::Word(word) | Self::Forms { word, .. } => word,
+        }
+    }
+
+    fn plural(&self) -> Option<&str> {
+        match self {
+            Self::Word(_) => None,
+            Self::Forms { plural, .. } => plural.as_deref(),
+        }
+    }
+
+    fn gender(&self) -> Option<Gender> {
+        match self {
+            Self::Word(_) => None,
+            Self::Forms { gender, .. } => *gender,
+        }
+    }
+
+    fn form(&self, key: &str) -> Option<&str> {
+        match self {
+            Self::Word(_) => None,
+            Self::Forms { forms, .. } => forms.get(key).map(String::as_str),
+        }
+    }
+
+    fn weight(&self) -> f32 {
+        match self {
+            Self::Word(_) => 1.0,
+            Self::Forms { weight, .. } => weight.unwrap_or(1.0),
+        }
+    }
+
+    fn has_tags(&self, wanted: &[String]) -> bool {
+        match self {
+            Self::Word(_) => wanted.is_empty(),
+            Self::Forms { tags, .. } => wanted.iter().all(|tag| tags.contains(tag)),
+        }
+    }
+
+    fn is_indeclinable(&self) -> bool {
+        self.plural() == Some(self.word())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Dictionaries {
+    #[serde(flatten)]
+    dictionaries: HashMap<String, Vec<DictionaryEntry>>,
+}
+
+impl Dictionaries {
+    /// Takes every dictionary missing here from `reference`, and notes the ones
+    /// that are shorter than their reference counterpart. Shorter lists are kept
+    /// as they are, since mixing reference-language words into them would read
+    /// worse than a smaller pool.
+    fn fill_from_reference(
+        &mut self,
+        lang: &str,
+        reference: Dictionaries,
+    ) -> Vec<MissingTranslation> {
+        let mut missing = Vec::new();
+        for (key, reference_entries) in reference.dictionaries {
+            match self.dictionaries.get(&key) {
+                None => {
+                    missing.push(MissingTranslation::DictionaryKey {
+                        lang: lang.to_string(),
+                        key: key.clone(),
+                    });
+                    self.dictionaries.insert(key, reference_entries);
+                }
+                Some(entries) if entries.len() < reference_entries.len() => {
+                    missing.push(MissingTranslation::DictionaryEntries {
+                        lang: lang.to_string(),
+                        key,
+                        missing: reference_entries.len() - entries.len(),
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+        missing
+    }
+}
+
+/// Categories of generated text shipped with the game. Others can be added by
+/// giving `templates.json` a `<CATEGORY>_TEMPLATES` list, and generated with
+/// [`WhackaMoleeGenerator::generate`].
+pub mod category {
+    pub const TAGLINE: &str = "TAGLINE";
+    pub const TEAM_NAME: &str = "TEAM_NAME";
+    pub const TERRAIN_NAME: &str = "TERRAIN_NAME";
+    pub const MOLE_UNIT_NAME: &str = "MOLE_UNIT_NAME";
+    pub const WEAPON_NAME: &str = "WEAPON_NAME";
+    pub const VICTORY_QUIP: &str = "VICTORY_QUIP";
+    pub const OBITUARY: &str = "OBITUARY";
+    pub const CRATE_ANNOUNCEMENT: &str = "CRATE_ANNOUNCEMENT";
+    pub const COMMENTARY_DEATH: &str = "COMMENTARY_DEATH";
+    pub const COMMENTARY_DIRECT_HIT: &str = "COMMENTARY_DIRECT_HIT";
+    pub const COMMENTARY_DROWNED: &str = "COMMENTARY_DROWNED";
+    pub const COMMENTARY_TURN_TIMEOUT: &str = "COMMENTARY_TURN_TIMEOUT";
+}
+
+/// Suffix of every template list's key in `templates.json`.
+const TEMPLATES_KEY_SUFFIX: &str = "_TEMPLATES";
+
+/// `templates.json` as written: template lists, plus an optional
+/// `"MAX_LENGTHS": { "TEAM_NAME": 32 }` limiting categories to a number of
+/// characters, e.g. to fit name plates.
+#[derive(Deserialize)]
+struct TemplatesFile {
+    #[serde(rename = "MAX_LENGTHS", default)]
+    max_lengths: BTreeMap<String, usize>,
+    #[serde(flatten)]
+    lists: BTreeMap<String, Vec<Template>>,
+}
+
+/// Templates by category, parsed when loaded.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(try_from = "TemplatesFile")]
+struct Templates {
+    categories: BTreeMap<String, Vec<Template>>,
+    max_lengths: BTreeMap<String, usize>,
+}
+
+impl TryFrom<TemplatesFile> for Templates {
+    type Error = String;
+
+    fn try_from(file: TemplatesFile) -> Result<Self, Self::Error> {
+        let categories: BTreeMap<String, Vec<Template>> = file
+            .lists
+            .into_iter()
+            .map(
+                |(key, templates)| match key.strip_suffix(TEMPLATES_KEY_SUFFIX) {
+                    Some(category) if !category.is_empty() => Ok((category.to_string(), templates)),
+                    _ => Err(format!(
+                        "'{}' is not a template list; keys end in {}",
+                        key, TEMPLATES_KEY_SUFFIX
+                    )),
+                },
+            )
+            .collect::<Result<_, _>>()?;
+        if let Some(unknown) = file
+            .max_lengths
+            .keys()
+            .find(|category| !categories.contains_key(*category))
+        {
+            return Err(format!(
+                "MAX_LENGTHS limits '{}', which has no templates",
+                unknown
+            ));
+        }
+        Ok(Self {
+            categories,
+            max_lengths: file.max_lengths,
+        })
+    }
+}
+
+impl Templates {
+    /// Every category's templates, by category name.
+    fn categories(&self) -> impl Iterator<Item = (&str, &Vec<Template>)> {
+        self.categories
+            .iter()
+            .map(|(category, templates)| (category.as_str(), templates))
+    }
+
+    fn get(&self, category: &str) -> Option<&Vec<Template>> {
+        self.categories.get(category)
+    }
+
+    fn max_length(&self, category: &str) -> Option<usize> {
+        self.max_lengths.get(category).copied()
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct WhackaMoleeGenerator {
+    dictionaries: Dictionaries,
+    templates: Templates,
+    missing_translations: Vec<MissingTranslation>,
+    grammar: &'static dyn GrammarRules,
+    /// Output goes through pseudo-localization, see [`PSEUDO_LANG`].
+    pseudolocalized: bool,
+    recent_names: RecentNames,
+    validation_report: ValidationReport,
+}
+
+impl WhackaMoleeGenerator {
+    /// Reads a language's files straight from `base_locales_path_str`, blocking.
+    /// Meant for tools and tests; the game loads them through [`TextGeneratorPlugin`].
+    pub fn new(
+        base_locales_path_str: &str,
+        current_lang_id_str: &str,
+    ) -> Result<Self, TextGeneratorError> {
+        info!(
+            "Initializing WhackaMoleeGenerator for lang: {} from path: {}",
+            current_lang_id_str, base_locales_path_str
+        );
+
+        let lang = current_lang_id_str.to_lowercase();
+        let lang_path = PathBuf::from(base_locales_path_str).join(&lang);
+
+        let read = |path: PathBuf| {
+            fs::read_to_string(&path).map_err(|source| TextGeneratorError::Io { path, source })
+        };
+        let dictionaries_content = read(lang_path.join("dictionaries.json"))?;
+        let templates_content = read(lang_path.join("templates.json"))?;
+        let reference_content = if lang == REFERENCE_LANG {
+            None
+        } else {
+            let reference_path = PathBuf::from(base_locales_path_str)
+                .join(REFERENCE_LANG)
+                .join("dictionaries.json");
+            fs::read_to_string(reference_path)
+                .map_err(|e| {
+                    warn!(
+                        "TextGen: cannot read '{}' dictionaries for fallback: {:?}",
+                        REFERENCE_LANG, e
+                    )
+                })
+                .ok()
+        };
+
+        Self::from_json(
+            &lang,
+            &dictionaries_content,
+            &templates_content,
+            reference_content.as_deref(),
+        )
+    }
+
+    /// Builds a generator from the contents of `dictionaries.json` and `templates.json`.
+    /// Dictionaries missing from `lang` are taken from `reference_dictionaries_content`.
+    pub fn from_json(
+        lang: &str,
+        dictionaries_content: &str,
+        templates_content: &str,
+        reference_dictionaries_content: Option<&str>,
+    ) -> Result<Self, TextGeneratorError> {
+        let dictionaries: Dictionaries =
+            serde_json::from_str(dictionaries_content).map_err(|source| {
+                TextGeneratorError::Parse {
+                    file: "dictionaries.json",
+                    source,
+                }
+            })?;
+        let templates: Templates = serde_json::from_str(templates_content).map_err(|source| {
+            TextGeneratorError::Parse {
+                file: "templates.json",
+                source,
+            }
+        })?;
+        let reference = reference_dictionaries_content.and_then(|content| {
+            serde_json::from_str::<Dictionaries>(content)
+                .map_err(|e| {
+                    warn!(
+                        "TextGen: cannot parse '{}' dictionaries for fallback: {:?}",
+                        REFERENCE_LANG, e
+                    )
+                })
+                .ok()
+        });
+        Self::from_parts(lang, dictionaries, templates, reference)
+    }
+
+    /// Builds a generator from loaded [`DictionariesAsset`] and [`TemplatesAsset`]s.
+    /// Dictionaries missing from `lang` are taken from `reference_dictionaries`.
+    pub fn from_assets(
+        lang: &str,
+        dictionaries: &DictionariesAsset,
+        templates: &TemplatesAsset,
+        reference_dictionaries: Option<&DictionariesAsset>,
+    ) -> Result<Self, TextGeneratorError> {
+        Self::from_parts(
+            lang,
+            dictionaries.0.clone(),
+            templates.0.clone(),
+            reference_dictionaries.map(|reference| reference.0.clone()),
+        )
+    }
+
+    /// Builds and validates a generator. Warnings are logged; errors fail the
+    /// build, since the text would show them on screen.
+    fn from_parts(
+        lang: &str,
+        dictionaries: Dictionaries,
+        templates: Templates,
+        reference: Option<Dictionaries>,
+    ) -> Result<Self, TextGeneratorError> {
+        let mut generator = Self::unvalidated(lang, dictionaries, templates, reference);
+
+        let report = generator.validate(lang);
+        if !report.is_usable() {
+            return Err(TextGeneratorError::Invalid(report));
+        }
+        for warning in report.warnings() {
+            warn!("TextGen: '{}': {}", lang, warning);
+        }
+        generator.validation_report = report;
+        Ok(generator)
+    }
+
+    /// A generator as loaded, before validation.
+    fn unvalidated(
+        lang: &str,
+        mut dictionaries: Dictionaries,
+        templates: Templates,
+        reference: Option<Dictionaries>,
+    ) -> Self {
+        let missing_translations = match reference {
+            Some(reference) => dictionaries.fill_from_reference(lang, reference),
+            None => Vec::new(),
+        };
+        Self {
+            dictionaries,
+            templates,
+            missing_translations,
+            grammar: grammar_rules_for(lang),
+            pseudolocalized: false,
+            recent_names: RecentNames::default(),
+            validation_report: ValidationReport::default(),
+        }
+    }
+
+    /// Makes every generated string pseudo-localized, for the pseudo-locale
+    /// built on top of the reference language's files.
+    pub fn pseudolocalized(mut self) -> Self {
+        self.pseudolocalized = true;
+        self
+    }
+
+    /// Warnings found while loading this generator; it has no errors, or it
+    /// would not have loaded.
+    pub fn validation_report(&self) -> &ValidationReport {
+        &self.validation_report
+    }
+
+    /// Dictionary fallbacks taken while loading this generator.
+    pub fn missing_translations(&self) -> &[MissingTranslation] {
+        &self.missing_translations
+    }
+
+    /// Entries of `dict_name` carrying every one of `tags`.
+    fn entries_tagged<'a>(
+        &'a self,
+        dict_name: &str,
+        tags: &[String],
+    ) -> Option<impl Iterator<Item = &'a DictionaryEntry>> {
+        let items = self.dictionaries.dictionaries.get(dict_name)?;
+        Some(items.iter().filter(move |entry| entry.has_tags(tags)))
+    }
+
+    /// A word of `dict_name` carrying every one of `tags`, drawn by weight.
+    /// With a `word_limit`, only words of at most that many characters are
+    /// drawn, or the shortest ones when there are none.
+    fn get_random_from_dict<R: Rng + ?Sized>(
+        &self,
+        dict_name: &str,
+        tags: &[String],
+        word_limit: Option<usize>,
+        rng: &mut R,
+    ) -> Option<&DictionaryEntry> {
+        let mut entries: Vec<&DictionaryEntry> = self.entries_tagged(dict_name, tags)?.collect();
+        if let Some(word_limit) = word_limit {
+            let shortest = entries
+                .iter()
+                .map(|entry| entry.word().chars().count())
+                .min()?;
+            let word_limit = word_limit.max(shortest);
+            entries.retain(|entry| entry.word().chars().count() <= word_limit);
+        }
+        entries
+            .choose_weighted(rng, |entry| entry.weight())
+            .ok()
+            .copied()
+    }
+
+    /// The entry's explicit plural, or the one the language's rules produce.
+    fn pluralize(&self, entry: &DictionaryEntry) -> String {
+        match entry.plural() {
+            Some(plural) => plural.to_string(),
+            None => self.grammar.pluralize(entry.word()),
+        }
+    }
+
+    fn gender(&self, entry: &DictionaryEntry) -> Gender {
+        entry
+            .gender()
+            .unwrap_or_else(|| self.grammar.guess_gender(entry.word()))
+    }
+
+    /// A noun in `case`: the entry's own form if it gives one, else the rules'.
+    /// A form neither gives is logged unless `quiet`.
+    fn noun_form(&self, entry: &DictionaryEntry, plural: bool, case: Case, quiet: bool) -> String {
+        if entry.is_indeclinable() {
+            return entry.word().to_string();
+        }
+        let nominative = if plural {
+            self.pluralize(entry)
+        } else {
+            entry.word().to_string()
+        };
+        if case == Case::Nominative {
+            return nominative;
+        }
+        let gender = self.gender(entry);
+        let key = Agreement {
+            gender,
+            plural,
+            case,
+        }
+        .form_key(false);
+        if let Some(form) = entry.form(&key) {
+            return form.to_string();
+        }
+        self.grammar
+            .decline(&nominative, gender, plural, case)
+            .unwrap_or_else(|| {
+                if !quiet {
+                    warn!(
+                        "TextGen: no '{}' form of '{}'; add it to the dictionary",
+                        key,
+                        entry.word()
+                    );
+                }
+                nominative
+            })
+    }
+
+    /// An adjective or article agreeing with `agreement`.
+    fn agreeing_form(&self, entry: &DictionaryEntry, agreement: Agreement) -> String {
+        let key = agreement.form_key(true);
+        if key.is_empty() || entry.is_indeclinable() {
+            return entry.word().to_string();
+        }
+        match entry.form(&key) {
+            Some(form) => form.to_string(),
+            None => self.grammar.inflect_adjective(entry.word(), agreement),
+        }
+    }
+
+    /// The word `entry` as `placeholder` asks for it. `key` is the dictionary
+    /// the word was drawn from; `agreement` is that of the noun the placeholder
+    /// agrees with. Missing noun forms are logged unless `quiet`.
+    fn render(
+        &self,
+        placeholder: &Placeholder,
+        key: &str,
+        entry: &DictionaryEntry,
+        agreement: Option<Agreement>,
+        quiet: bool,
+    ) -> String {
+        let (text, agreement) = if key.starts_with("NOUN_") {
+            let case = placeholder.case.unwrap_or_default();
+            let agreement = Agreement {
+                gender: placeholder.gender.unwrap_or_else(|| self.gender(entry)),
+                plural: placeholder.plural,
+                case,
+            };
+            (
+                self.noun_form(entry, placeholder.plural, case, quiet),
+                agreement,
+            )
+        } else {
+            let agreement = agreement.unwrap_or_default();
+            let agreement = Agreement {
+                gender: placeholder.gender.unwrap_or(agreement.gender),
+                plural: placeholder.plural || agreement.plural,
+                case: placeholder.case.unwrap_or(agreement.case),
+            };
+            let text = if placeholder.gender.is_some()
+                || placeholder.case.is_some()
+                || placeholder.agree.is_some()
+            {
+                self.agreeing_form(entry, agreement)
+            } else if placeholder.plural {
+                self.pluralize(entry)
+            } else {
+                entry.word().to_string()
+            };
+            (text, agreement)
+        };
+        let text = if placeholder.article {
+            self.grammar.with_indefinite_article(&text, agreement)
+        } else {
+            text
+        };
+        if placeholder.capitalize {
+            capitalize(&text)
+        } else {
+            text
+        }
+    }
+
+    /// A `{$NAME}` slot filled with `value`, which is used as it is.
+    fn render_context_value(&self, placeholder: &Placeholder, value: &str) -> String {
+        let text = if placeholder.article {
+            self.grammar
+                .with_indefinite_article(value, Agreement::default())
+        } else {
+            value.to_string()
+        };
+        if placeholder.capitalize {
+            capitalize(&text)
+        } else {
+            text
+        }
+    }
+
+    /// Fills every slot of `template`. Words are drawn first, so a slot can
+    /// agree with or repeat a word named anywhere in the template.
+    fn process_template<R: Rng + ?Sized>(
+        &self,
+        template: &Template,
+        context: &TextContext,
+        word_limit: Option<usize>,
+        rng: &mut R,
+    ) -> String {
+        let drawn: Vec<Option<(&str, &DictionaryEntry)>> = template
+            .slots()
+            .map(|placeholder| {
+                let key = placeholder.dictionary_key()?;
+                self.get_random_from_dict(key, &placeholder.tags, word_limit, rng)
+                    .map(|entry| (key, entry))
+            })
+            .collect();
+
+        let mut named: HashMap<&str, (&str, &DictionaryEntry)> = HashMap::new();
+        let mut agreements: HashMap<&str, Agreement> = HashMap::new();
+        for (placeholder, drawn) in template.slots().zip(&drawn) {
+            if let (Some(name), Some((key, entry))) = (&placeholder.name, drawn) {
+                named.insert(name, (key, entry));
+                if key.starts_with("NOUN_") {
+                    agreements.insert(
+                        name,
+                        Agreement {
+                            gender: placeholder.gender.unwrap_or_else(|| self.gender(entry)),
+                            plural: placeholder.plural,
+                            case: placeholder.case.unwrap_or_default(),
+                        },
+                    );
+                }
+            }
+        }
+
+        let mut result = String::with_capacity(template.source().len());
+        let mut drawn = drawn.into_iter();
+        for segment in template.segments() {
+            let placeholder = match segment {
+                Segment::Literal(range) => {
+                    result.push_str(template.text(range));
+                    continue;
+                }
+                Segment::Slot(placeholder) => placeholder,
+            };
+            let word = match &placeholder.source {
+                Source::Dictionary(_) => drawn.next().flatten(),
+                Source::Reference(name) => {
+                    drawn.next();
+                    named.get(name.as_str()).copied()
+                }
+                Source::Context(name) => {
+                    drawn.next();
+                    match context.get(name) {
+                        Some(value) => {
+                            result.push_str(&self.render_context_value(placeholder, value))
+                        }
+                        None => {
+                            warn!(
+                                "TextGen: no '{}' in the context of '{}'",
+                                name,
+                                template.source()
+                            );
+                            result.push_str(template.text(&placeholder.range));
+                        }
+                    }
+                    continue;
+                }
+            };
+            match word {
+                Some((key, entry)) => {
+                    let agreement = placeholder
+                        .agree
+                        .as_deref()
+                        .and_then(|name| agreements.get(name).copied());
+                    result.push_str(&self.render(placeholder, key, entry, agreement, false));
+                }
+                // The dictionary is missing or has no word for the tags, which
+                // was reported on load.
+                None => result.push_str(template.text(&placeholder.range)),
+            }
+        }
+        if self.pseudolocalized {
+            pseudo::pseudolocalize(&result)
+        } else {
+            result
+        }
+    }
+
+    /// Text of `category`, e.g. [`category::VICTORY_QUIP`].
+    pub fn generate(&self, category: &str) -> String {
+        self.generate_with_rng(category, &mut rand::rng())
+    }
+
+    /// Like [`Self::generate`], drawing every choice from `rng`.
+    pub fn generate_with_rng<R: Rng + ?Sized>(&self, category: &str, rng: &mut R) -> String {
+        self.generate_with_context_and_rng(category, &TextContext::default(), rng)
+    }
+
+    /// Text of `category` with its `{$NAME}` slots filled from `context`.
+    pub fn generate_with_context(&self, category: &str, context: &TextContext) -> String {
+        self.generate_with_context_and_rng(category, context, &mut rand::rng())
+    }
+
+    /// Like [`Self::generate_with_context`], drawing every choice from `rng`.
+    pub fn generate_with_context_and_rng<R: Rng + ?Sized>(
+        &self,
+        category: &str,
+        context: &TextContext,
+        rng: &mut R,
+    ) -> String {
+        match self
+            .templates
+            .get(category)
+            .and_then(|templates| templates.choose(rng))
+        {
+            Some(template) => self.process_template(template, context, None, rng),
+            None => {
+                warn!("TextGen: No {} templates available.", category);
+                format!("ERR_NO_{}{}", category, TEMPLATES_KEY_SUFFIX)
+            }
+        }
+    }
+
+    /// Categories this language has templates for.
+    pub fn categories(&self) -> impl Iterator<Item = &str> {
+        self.templates.categories().map(|(category, _)| category)
+    }
+
+    /// Roughly how many distinct texts each template of `category` can
+    /// produce: the product of how many words each slot can draw. Modifiers
+    /// and repeated words are not taken into account. `None` for an unknown
+    /// category.
+    pub fn template_combinations(&self, category: &str) -> Option<Vec<(&str, u128)>> {
+        let templates = self.templates.get(category)?;
+        let combinations = templates
+            .iter()
+            .map(|template| {
+                let count = template
+                    .slots()
+                    .filter_map(|slot| {
+                        let entries = self.entries_tagged(slot.dictionary_key()?, &slot.tags);
+                        Some(entries.map_or(0, |entries| {
+                            entries.filter(|entry| entry.weight() > 0.0).count()
+                        }) as u128)
+                    })
+                    .fold(1, u128::saturating_mul);
+                (template.source(), count)
+            })
+            .collect();
+        Some(combinations)
+    }
+
+    pub fn generate_tagline(&self) -> String {
+        self.generate(category::TAGLINE)
+    }
+
+    /// Kept within the `MAX_LENGTHS` limit whenever a name can be made to fit.
+    pub fn generate_team_name(&self) -> String {
+        self.generate_team_name_with_rng(&mut rand::rng())
+    }
+
+    /// Kept within the `MAX_LENGTHS` limit whenever a name can be made to fit.
+    pub fn generate_terrain_name(&self) -> String {
+        self.generate_terrain_name_with_rng(&mut rand::rng())
+    }
+
+    /// Like [`Self::generate_tagline`], drawing every choice from `rng`.
+    pub fn generate_tagline_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        self.generate_with_rng(category::TAGLINE, rng)
+    }
+
+    /// Like [`Self::generate_team_name`], drawing every choice from `rng`.
+    pub fn generate_team_name_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        self.generate_fitting_or_any_with_rng(category::TEAM_NAME, rng)
+    }
+
+    /// Like [`Self::generate_terrain_name`], drawing every choice from `rng`.
+    pub fn generate_terrain_name_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        self.generate_fitting_or_any_with_rng(category::TERRAIN_NAME, rng)
+    }
+}
+
+/// `text` with its first letter upper-cased.
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// RNG for reproducible generation. ChaCha8's output is specified, so a seed gives
+/// the same names on every platform and with every `rand` release, unlike `StdRng`.
+pub type GeneratorRng = ChaCha8Rng;
+
+/// RNG for the `*_with_rng` methods, e.g. seeded from a match seed.
+/// The same seed, language and locale files always produce the same text.
+pub fn seeded_rng(seed: u64) -> GeneratorRng {
+    GeneratorRng::seed_from_u64(seed)
+}
+
+/// Loads every language's dictionaries and templates through the asset server,
+/// and keeps a [`WhackaMoleeGenerator`] for the current language once they arrive.
+pub struct TextGeneratorPlugin;
+
+impl Plugin for TextGeneratorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TextGeneratorFiles>()
+            .init_asset::<DictionariesAsset>()
+            .init_asset::<TemplatesAsset>()
+            .init_asset_loader::<assets::DictionariesLoader>()
+            .init_asset_loader::<assets::TemplatesLoader>()
+            .add_systems(
+                Update,
+                assets::load_text_generator_files
+                    .run_if(resource_changed::<AvailableLanguages>)
+                    .after(LocalizationSystemSet::Discovery)
+                    .before(LocalizationSystemSet::LanguageProcessing),
+            )
+            .add_systems(
+                Update,
+                reload_text_generator_on_lang_change
+                    .after(LocalizationSystemSet::LanguageProcessing),
+            )
+            .add_systems(PostUpdate, record_dictionary_fallbacks);
+    }
+}
+
+fn record_dictionary_fallbacks(
+    text_generator_res: Option<Res<WhackaMoleeGenerator>>,
+    mut missing_translations: ResMut<MissingTranslations>,
+) {
+    let Some(generator) = text_generator_res else {
+        return;
+    };
+    if generator.is_changed() {
+        for missing in generator.missing_translations() {
+            missing_translations.record(missing.clone());
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn reload_text_generator_on_lang_change(
+    current_lang: Res<CurrentLang>,
+    text_generator_res: Option<ResMut<WhackaMoleeGenerator>>,
+    mut lang_changed_event: EventReader<LanguageChangeRequest>,
+    mut dictionary_events: EventReader<AssetEvent<DictionariesAsset>>,
+    mut template_events: EventReader<AssetEvent<TemplatesAsset>>,
+    files: Res<TextGeneratorFiles>,
+    dictionary_assets: Res<Assets<DictionariesAsset>>,
+    template_assets: Res<Assets<TemplatesAsset>>,
+    mut commands: Commands,
+) {
+    let lang_code = current_lang.0.to_lowercase();
+    let is_pseudo = lang_code == PSEUDO_LANG;
+    // The pseudo-locale has no files of its own; it is derived from the reference ones.
+    let source_lang = if is_pseudo {
+        REFERENCE_LANG
+    } else {
+        lang_code.as_str()
+    };
+
+    let mut needs_reload = false;
+    if !lang_changed_event.is_empty() {
+        needs_reload = true;
+        lang_changed_event.clear();
+    } else if current_lang.is_changed() {
+        needs_reload = true;
+    }
+    // Loads and edits on disk of this language's files, or of the dictionaries it falls back to.
+    needs_reload |= files
+        .changed_dictionaries(&mut dictionary_events)
+        .iter()
+        .any(|lang| *lang == source_lang || *lang == REFERENCE_LANG);
+    needs_reload |= files
+        .changed_templates(&mut template_events)
+        .contains(&source_lang);
+
+    if needs_reload {
+        let (Some(dictionaries), Some(templates)) = (
+            files.dictionaries(source_lang, &dictionary_assets),
+            files.templates(source_lang, &template_assets),
+        ) else {
+            debug!(
+                "TextGen: locale files for '{}' not loaded yet, reload postponed",
+                lang_code
+            );
+            return;
+        };
+        let reference = if source_lang == REFERENCE_LANG {
+            None
+        } else {
+            files.dictionaries(REFERENCE_LANG, &dictionary_assets)
+        };
+        let new_gen = match WhackaMoleeGenerator::from_assets(
+            source_lang,
+            dictionaries,
+            templates,
+            reference,
+        ) {
+            Ok(new_gen) => new_gen,
+            Err(e) => {
+                error!(
+                    "Failed to reload/initialize WhackaMoleeGenerator for {}: {}",
+                    lang_code, e
+                );
+                return;
+            }
+        };
+        let new_gen = if is_pseudo {
+            new_gen.pseudolocalized()
+        } else {
+            new_gen
+        };
+        if let Some(mut generator_instance) = text_generator_res {
+            // The session's names stay recent across reloads.
+            let recent_names = std::mem::take(&mut generator_instance.recent_names);
+            *generator_instance = WhackaMoleeGenerator {
+                recent_names,
+                ..new_gen
+            };
+            info!("WhackaMoleeGenerator reloaded for language: {}", lang_code);
+        } else {
+            commands.insert_resource(new_gen);
+            info!(
+                "WhackaMoleeGenerator initialized for language: {}",
+                lang_code
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shipped_generator(lang: &str) -> WhackaMoleeGenerator {
+        let base_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/locales");
+        WhackaMoleeGenerator::new(base_path.to_str().unwrap(), lang).unwrap()
+    }
+
+    fn match_texts(generator: &WhackaMoleeGenerator, seed: u64) -> Vec<String> {
+        let mut rng = seeded_rng(seed);
+        vec![
+            generator.generate_tagline_with_rng(&mut rng),
+            generator.generate_team_name_with_rng(&mut rng),
+            generator.generate_team_name_with_rng(&mut rng),
+            generator.generate_terrain_name_with_rng(&mut rng),
+        ]
+    }
+
+    #[test]
+    fn same_seed_gives_same_texts() {
+        for lang in ["en", "es", "pl"] {
+            let first = match_texts(&shipped_generator(lang), 42);
+            let second = match_texts(&shipped_generator(lang), 42);
+            assert_eq!(first, second, "language {}", lang);
+        }
+    }
+
+    /// `templates.json` with `template` as the only one of every category.
+    fn templates_json(template: &str) -> String {
+        serde_json::json!({
+            "TAGLINE_TEMPLATES": [template],
+            "TEAM_NAME_TEMPLATES": [template],
+            "TERRAIN_NAME_TEMPLATES": [template],
+        })
+        .to_string()
+    }
+
+    fn generator_with_tagline(
+        lang: &str,
+        dictionaries: &str,
+        tagline: &str,
+    ) -> WhackaMoleeGenerator {
+        WhackaMoleeGenerator::from_json(lang, dictionaries, &templates_json(tagline), None).unwrap()
+    }
+
+    #[test]
+    fn placeholders_take_modifiers() {
+        let dictionaries = r#"{ "NOUN_VEGETABLE": ["onion"], "ADJ_TASTE": ["spicy"] }"#;
+        let tagline =
+            |template| generator_with_tagline("en", dictionaries, template).generate_tagline();
+        assert_eq!(
+            tagline("{NOUN_VEGETABLE|a|capitalize} for NATO"),
+            "An onion for NATO"
+        );
+        assert_eq!(tagline("{ADJ_TASTE|a} {NOUN_VEGETABLE}"), "a spicy onion");
+        assert_eq!(
+            tagline("{NOUN_VEGETABLE|plural|as=veg}, {veg|capitalize}!"),
+            "onions, Onion!"
+        );
+
+        let dictionaries = r#"{ "NOUN_VEGETABLE": ["marchewka"], "ADJ_TASTE": ["ostry"] }"#;
+        let generator = generator_with_tagline(
+            "pl",
+            dictionaries,
+            "{ADJ_TASTE|agree=veg|capitalize} {NOUN_VEGETABLE|plural|as=veg}",
+        );
+        assert_eq!(generator.generate_tagline(), "Ostre marchewki");
+    }
+
+    #[test]
+    fn categories_come_from_the_templates_file() {
+        for lang in ["en", "es", "pl"] {
+            let generator = shipped_generator(lang);
+            for name in [
+                category::MOLE_UNIT_NAME,
+                category::WEAPON_NAME,
+                category::VICTORY_QUIP,
+                category::OBITUARY,
+                category::CRATE_ANNOUNCEMENT,
+            ] {
+                assert!(
+                    generator.categories().any(|known| known == name),
+                    "{} has no {}",
+                    lang,
+                    name
+                );
+                assert!(
+                    !generator.generate(name).starts_with("ERR_"),
+                    "{} {}",
+                    lang,
+                    name
+                );
+            }
+        }
+
+        let templates = r#"{
+            "TAGLINE_TEMPLATES": ["x"],
+            "TEAM_NAME_TEMPLATES": ["x"],
+            "TERRAIN_NAME_TEMPLATES": ["x"],
+            "BURROW_SIGN_TEMPLATES": ["Keep out, {NOUN_VEGETABLE|plural}!"]
+        }"#;
+        let generator = WhackaMoleeGenerator::from_json(
+            "en",
+            r#"{ "NOUN_VEGETABLE": ["onion"] }"#,
+            templates,
+            None,
+        )
+        .unwrap();
+        assert_eq!(generator.generate("BURROW_SIGN"), "Keep out, onions!");
+        assert_eq!(
+            generator.generate("SHOPPING_LIST"),
+            "ERR_NO_SHOPPING_LIST_TEMPLATES"
+        );
+    }
+
+    #[test]
+    fn tags_and_weights_steer_draws() {
+        let dictionaries = r#"{ "NOUN_WEAPON": [
+            "sword",
+            { "word": "grenade", "tags": ["explosive"] },
+            { "word": "banana bomb", "tags": ["explosive", "joke"], "weight": 0 }
+        ] }"#;
+        let generator = generator_with_tagline("en", dictionaries, "{NOUN_WEAPON[explosive]}");
+        let mut rng = seeded_rng(1);
+        for _ in 0..50 {
+            assert_eq!(generator.generate_tagline_with_rng(&mut rng), "grenade");
+        }
+
+        let loaded = WhackaMoleeGenerator::from_json(
+            "en",
+            dictionaries,
+            &templates_json("{NOUN_WEAPON[explosive,joke]}"),
+            None,
+        );
+        let Err(TextGeneratorError::Invalid(report)) = loaded else {
+            panic!("a slot no word can fill loaded");
+        };
+        assert_eq!(
+            report.errors().collect::<Vec<_>>(),
+            [&ValidationIssue::NoTaggedWords {
+                key: "NOUN_WEAPON".to_string(),
+                tags: vec!["explosive".to_string(), "joke".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn context_values_fill_dollar_slots() {
+        let dictionaries = r#"{ "ADJ_STATE": ["pureed"] }"#;
+        let generator = generator_with_tagline(
+            "en",
+            dictionaries,
+            "{$victim} was {ADJ_STATE} by {$weapon|a}",
+        );
+        let context = TextContext::new()
+            .with("victim", "Sgt. Parsnip")
+            .with("weapon", "Turnip Mortar");
+        assert_eq!(
+            generator.generate_with_context(category::TAGLINE, &context),
+            "Sgt. Parsnip was pureed by a Turnip Mortar"
+        );
+        assert_eq!(
+            generator.generate_tagline(),
+            "{$victim} was pureed by {$weapon|a}"
+        );
+    }
+
+    #[test]
+    fn limited_texts_fit_or_report_it() {
+        let dictionaries = r#"{ "NOUN_VEGETABLE": ["aubergine", "pea"] }"#;
+        let templates = r#"{
+            "MAX_LENGTHS": { "TEAM_NAME": 12 },
+            "TAGLINE_TEMPLATES": ["x"],
+            "TEAM_NAME_TEMPLATES": [
+                "The {NOUN_VEGETABLE|plural} of doom",
+                "{NOUN_VEGETABLE|plural|capitalize}"
+            ],
+            "TERRAIN_NAME_TEMPLATES": ["x"]
+        }"#;
+        let generator =
+            WhackaMoleeGenerator::from_json("en", dictionaries, templates, None).unwrap();
+        assert_eq!(generator.max_length(category::TEAM_NAME), Some(12));
+        let mut rng = seeded_rng(3);
+        for _ in 0..50 {
+            assert!(
+                generator
+                    .generate_team_name_with_rng(&mut rng)
+                    .chars()
+                    .count()
+                    <= 12
+            );
+            assert_eq!(
+                generator.generate_limited_with_rng(
+                    category::TEAM_NAME,
+                    TextLimit::Chars(5),
+                    &mut rng
+                ),
+                Ok("Peas".to_string())
+            );
+        }
+        let fits = |text: &str| text.ends_with("doom");
+        assert_eq!(
+            generator
+                .generate_limited_with_rng(category::TEAM_NAME, TextLimit::Fits(&fits), &mut rng)
+                .map(|text| text.len() > 12),
+            Ok(true)
+        );
+        assert_eq!(
+            generator.generate_limited(category::TEAM_NAME, TextLimit::Chars(3)),
+            Err(TextDoesNotFit {
+                category: "TEAM_NAME".to_string()
+            })
+        );
+
+        let templates = r#"{
+            "MAX_LENGTHS": { "BURROW_SIGN": 10 },
+            "TAGLINE_TEMPLATES": ["x"],
+            "TEAM_NAME_TEMPLATES": ["x"],
+            "TERRAIN_NAME_TEMPLATES": ["x"]
+        }"#;
+        let loaded = WhackaMoleeGenerator::from_json("en", dictionaries, templates, None);
+        assert!(matches!(loaded, Err(TextGeneratorError::Parse { .. })));
+    }
+
+    #[test]
+    fn broken_templates_fail_to_load() {
+        for template in ["{NOUN_VEGETABLE|shiny}", "{ADJ_TASTE|agree=veg}", "{veg}"] {
+            let loaded =
+                WhackaMoleeGenerator::from_json("en", "{}", &templates_json(template), None);
+            assert!(
+                matches!(loaded, Err(TextGeneratorError::Parse { .. })),
+                "{}",
+                template
+            );
+        }
+    }
+
+    #[test]
+    fn validation_reports_unusable_data() {
+        for lang in ["en", "es", "pl"] {
+            assert!(shipped_generator(lang).validation_report().is_usable());
+        }
+
+        let dictionaries =
+            r#"{ "NOUN_VEGETABLE": ["onion", "{NOUN_VEGETABLE} soup"], "ADJ_TASTE": [] }"#;
+        let templates = r#"{
+            "TAGLINE_TEMPLATES": ["{ADJ_TASTE} {NOUN_VEGETABLE} {NOUN_WEAPON}"],
+            "TEAM_NAME_TEMPLATES": ["{NOUN_VEGETABLE}"],
+            "TERRAIN_NAME_TEMPLATES": []
+        }"#;
+        let Err(TextGeneratorError::Invalid(report)) =
+            WhackaMoleeGenerator::from_json("en", dictionaries, templates, None)
+        else {
+            panic!("invalid data loaded");
+        };
+        let errors: Vec<&ValidationIssue> = report.errors().collect();
+        assert_eq!(
+            errors,
+            [
+                &ValidationIssue::EmptyTemplateCategory {
+                    category: "TERRAIN_NAME".to_string()
+                },
+                &ValidationIssue::UnknownDictionaryKey {
+                    category: "TAGLINE".to_string(),
+                    template: "{ADJ_TASTE} {NOUN_VEGETABLE} {NOUN_WEAPON}".to_string(),
+                    key: "NOUN_WEAPON".to_string(),
+                },
+                &ValidationIssue::EmptyDictionary {
+                    key: "ADJ_TASTE".to_string()
+                },
+                &ValidationIssue::PlaceholderInWord {
+                    key: "NOUN_VEGETABLE".to_string(),
+                    word: "{NOUN_VEGETABLE} soup".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn unique_batches_have_distinct_names() {
+        let mut generator = shipped_generator("en");
+        let mut rng = seeded_rng(3);
+        let first = generator
+            .generate_unique_team_names_with_rng(8, &mut rng)
+            .unwrap();
+        let second = generator
+            .generate_unique_team_names_with_rng(8, &mut rng)
+            .unwrap();
+        let distinct: std::collections::HashSet<&String> = first.iter().chain(&second).collect();
+        assert_eq!(distinct.len(), 16);
+
+        let dictionaries = r#"{ "NOUN_VEGETABLE": ["onion", "leek"] }"#;
+        let mut generator = WhackaMoleeGenerator::from_json(
+            "en",
+            dictionaries,
+            &templates_json("{NOUN_VEGETABLE}"),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            generator
+                .generate_unique_team_names_with_rng(2, &mut rng)
+                .map(|names| names.len()),
+            Ok(2)
+        );
+        let error = generator
+            .generate_unique_team_names_with_rng(3, &mut rng)
+            .unwrap_err();
+        assert_eq!((error.requested, error.names.len()), (3, 2));
+
+        let error = generator
+            .generate_unique_team_names_with_rng(usize::MAX, &mut rng)
+            .unwrap_err();
+        assert_eq!((error.requested, error.names.len()), (usize::MAX, 0));
+    }
+
+    #[test]
+    fn different_seeds_give_different_texts() {
+        let generator = shipped_generator("en");
+        let first = match_texts(&generator, 1);
+        assert!((2..10).any(|seed| match_texts(&generator, seed) != first));
+    }
+}
@@ -0,0 +1,438 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Range;
+
+use super::grammar::{Case, Gender};
+
+/// Matches placeholders such as `{NOUN_VEGETABLE}` or `{NOUN_VEGETABLE|plural|capitalize}`:
+/// a dictionary key, or the name of a word picked elsewhere in the template,
+/// followed by modifiers separated by `|`. Text outside braces is never touched,
+/// so templates can use capitalized words and acronyms freely.
+///
+/// `{$NAME}` is filled with a value given when generating, such as the name of
+/// the mole an event happened to; it takes only `capitalize` and `a`.
+///
+/// A dictionary key can be followed by tags in brackets, `{NOUN_WEAPON[explosive]}`,
+/// to draw only the words carrying all of them.
+///
+/// Modifiers:
+/// - `plural`: the plural of the word.
+/// - `capitalize`: upper-cases the first letter.
+/// - `a`: puts the language's indefinite article before the word ("an onion").
+/// - `as=NAME`: names the picked word. `{NAME}` elsewhere repeats it, with its
+///   own modifiers, and `agree=NAME` makes a slot agree with it.
+/// - `agree=NAME`: agrees in gender, number and case with the noun named `NAME`,
+///   so `{ADJ_TASTE|agree=veg} {NOUN_VEGETABLE|plural|as=veg}` gives "ostre marchewki".
+/// - a case (`nom`, `gen`, `dat`, `acc`, `ins`, `loc`) or a gender (`m`, `mp`,
+///   `f`, `n`), e.g. `{NOUN_TERRAIN|gen}` or `{ADJ_TASTE|gen|f}` before a fixed
+///   feminine noun.
+const PLACEHOLDER_PATTERN: &str = r"\{([^{}|]*)((?:\|[^{}|]*)*)\}";
+/// A dictionary key with optional tags, e.g. `NOUN_WEAPON[explosive,rare]`.
+const DICTIONARY_KEY_PATTERN: &str = r"^([A-Z][A-Z0-9_]*)(?:\[([^\[\]]*)\])?$";
+const TAG_PATTERN: &str = r"^[a-z][a-z0-9_-]*$";
+const NAME_PATTERN: &str = r"^[a-z][a-z0-9_]*$";
+/// Placeholders before braces were required: a dictionary key in capitals, e.g.
+/// `NOUN_VEGETABLE_PLURAL:gen#1`, with `#N` for agreement groups. Words in
+/// capitals that are not dictionary keys also match and are left alone.
+const LEGACY_PLACEHOLDER_PATTERN: &str =
+    r"\b([A-Z][A-Z_]*?[A-Z])(_PLURAL)?((?::[a-z]+)*)(?:#([0-9]+))?\b";
+
+static PLACEHOLDER_RE: Lazy<Regex> = Lazy::new(|| Regex::new(PLACEHOLDER_PATTERN).unwrap());
+static DICTIONARY_KEY_RE: Lazy<Regex> = Lazy::new(|| Regex::new(DICTIONARY_KEY_PATTERN).unwrap());
+static NAME_RE: Lazy<Regex> = Lazy::new(|| Regex::new(NAME_PATTERN).unwrap());
+static TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(TAG_PATTERN).unwrap());
+
+/// Where a placeholder's word comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Source {
+    /// A word drawn from the dictionary with this key.
+    Dictionary(String),
+    /// The word picked by the placeholder named with `as=`.
+    Reference(String),
+    /// A value from the context the text is generated for, written `$NAME`.
+    Context(String),
+}
+
+/// One placeholder of a template, with its modifiers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Placeholder {
+    pub range: Range<usize>,
+    pub source: Source,
+    /// Tags every word drawn for this slot must carry.
+    pub tags: Vec<String>,
+    pub plural: bool,
+    pub gender: Option<Gender>,
+    pub case: Option<Case>,
+    pub name: Option<String>,
+    pub agree: Option<String>,
+    pub capitalize: bool,
+    pub article: bool,
+}
+
+impl Placeholder {
+    fn parse(caps: &regex::Captures) -> Result<Placeholder, String> {
+        let whole = caps.get(0).unwrap();
+        let mut tags = Vec::new();
+        let source = match caps[1].trim() {
+            name if NAME_RE.is_match(name) => Source::Reference(name.to_string()),
+            context
+                if context
+                    .strip_prefix('$')
+                    .is_some_and(|name| NAME_RE.is_match(name)) =>
+            {
+                Source::Context(context[1..].to_string())
+            }
+            other => {
+                let Some(key) = DICTIONARY_KEY_RE.captures(other) else {
+                    return Err(format!(
+                        "'{}' in {} is neither a dictionary key nor a name",
+                        other,
+                        whole.as_str()
+                    ));
+                };
+                if let Some(list) = key.get(2) {
+                    for tag in list.as_str().split(',').map(str::trim) {
+                        if !TAG_RE.is_match(tag) {
+                            return Err(format!("invalid tag '{}' in {}", tag, whole.as_str()));
+                        }
+                        tags.push(tag.to_string());
+                    }
+                }
+                Source::Dictionary(key[1].to_string())
+            }
+        };
+        let mut placeholder = Placeholder {
+            range: whole.range(),
+            source,
+            tags,
+            plural: false,
+            gender: None,
+            case: None,
+            name: None,
+            agree: None,
+            capitalize: false,
+            article: false,
+        };
+        for modifier in caps[2].split('|').skip(1).map(str::trim) {
+            let named = |prefix: &str| {
+                modifier
+                    .strip_prefix(prefix)
+                    .map(|name| match NAME_RE.is_match(name) {
+                        true => Ok(name.to_string()),
+                        false => Err(format!("invalid name '{}' in {}", name, whole.as_str())),
+                    })
+                    .transpose()
+            };
+            if let Some(name) = named("as=")? {
+                placeholder.name = Some(name);
+            } else if let Some(name) = named("agree=")? {
+                placeholder.agree = Some(name);
+            } else if modifier == "plural" {
+                placeholder.plural = true;
+            } else if modifier == "capitalize" {
+                placeholder.capitalize = true;
+            } else if modifier == "a" {
+                placeholder.article = true;
+            } else if let Some(gender) = Gender::from_tag(modifier) {
+                placeholder.gender = Some(gender);
+            } else if let Some(case) = Case::from_tag(modifier) {
+                placeholder.case = Some(case);
+            } else {
+                return Err(format!(
+                    "unknown modifier '{}' in {}",
+                    modifier,
+                    whole.as_str()
+                ));
+            }
+        }
+        if matches!(placeholder.source, Source::Context(_))
+            && (placeholder.plural
+                || placeholder.gender.is_some()
+                || placeholder.case.is_some()
+                || placeholder.name.is_some()
+                || placeholder.agree.is_some())
+        {
+            return Err(format!(
+                "context values take only capitalize and a, in {}",
+                whole.as_str()
+            ));
+        }
+        Ok(placeholder)
+    }
+
+    pub fn dictionary_key(&self) -> Option<&str> {
+        match &self.source {
+            Source::Dictionary(key) => Some(key),
+            Source::Reference(_) | Source::Context(_) => None,
+        }
+    }
+}
+
+/// A piece of a parsed template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Segment {
+    /// Text copied as it is, as a range of the template's source.
+    Literal(Range<usize>),
+    Slot(Placeholder),
+}
+
+/// A template parsed once, when its file is loaded, into the literal text and
+/// the slots between it. Parsing checks every placeholder and every name, so a
+/// broken template fails the load rather than showing up in a generated name.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub(super) struct Template {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Template, String> {
+        let mut segments = Vec::new();
+        let mut last_end = 0;
+        for caps in PLACEHOLDER_RE.captures_iter(source) {
+            let placeholder = Placeholder::parse(&caps)?;
+            if placeholder.range.start > last_end {
+                segments.push(Segment::Literal(last_end..placeholder.range.start));
+            }
+            last_end = placeholder.range.end;
+            segments.push(Segment::Slot(placeholder));
+        }
+        if last_end < source.len() {
+            segments.push(Segment::Literal(last_end..source.len()));
+        }
+        let template = Template {
+            source: source.to_string(),
+            segments,
+        };
+
+        let defined: Vec<&str> = template
+            .slots()
+            .filter_map(|slot| slot.name.as_deref())
+            .collect();
+        let undefined = template.slots().find_map(|slot| {
+            let used = match &slot.source {
+                Source::Reference(name) => Some(name),
+                Source::Dictionary(_) | Source::Context(_) => slot.agree.as_ref(),
+            }?;
+            (!defined.contains(&used.as_str())).then_some(used)
+        });
+        match undefined {
+            Some(name) => Err(format!("'{}' is not defined with as={}", name, name)),
+            None => Ok(template),
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// `range` of the source, for a [`Segment`] of this template.
+    pub fn text(&self, range: &Range<usize>) -> &str {
+        &self.source[range.clone()]
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Characters of the text outside the slots.
+    pub fn literal_length(&self) -> usize {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(range) => self.text(range).chars().count(),
+                Segment::Slot(_) => 0,
+            })
+            .sum()
+    }
+
+    pub fn slots(&self) -> impl Iterator<Item = &Placeholder> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Slot(placeholder) => Some(placeholder),
+            Segment::Literal(_) => None,
+        })
+    }
+}
+
+impl TryFrom<String> for Template {
+    type Error = String;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        Template::parse(&source).map_err(|e| format!("{} in template '{}'", e, source))
+    }
+}
+
+/// Dictionary keys a template refers to. Unparsable templates have none.
+pub fn template_dictionary_keys(template: &str) -> Vec<String> {
+    Template::parse(template)
+        .map(|template| {
+            template
+                .slots()
+                .filter_map(|slot| slot.dictionary_key().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Why `template` cannot be used, if it cannot: a malformed placeholder, or a
+/// name used without a placeholder defining it with `as=`.
+pub fn template_syntax_error(template: &str) -> Option<String> {
+    Template::parse(template).err()
+}
+
+/// Rewrites a template from the bare syntax used before braces were required:
+/// `ADJ_TASTE#1 NOUN_VEGETABLE_PLURAL#1` becomes
+/// `{ADJ_TASTE|agree=vegetable} {NOUN_VEGETABLE|plural|as=vegetable}`. The
+/// first noun of each agreement group is named after its dictionary. Only keys of
+/// `dictionary_keys` are rewritten, so acronyms such as "UFO" stay text.
+/// Templates that already use braces are returned as they are.
+pub fn migrate_legacy_template(template: &str, dictionary_keys: &BTreeSet<String>) -> String {
+    if template.contains('{') {
+        return template.to_string();
+    }
+    static LEGACY_PLACEHOLDER_RE: Lazy<Regex> =
+        Lazy::new(|| Regex::new(LEGACY_PLACEHOLDER_PATTERN).unwrap());
+    let re = &*LEGACY_PLACEHOLDER_RE;
+
+    // Name the noun leading each agreement group.
+    let mut group_names: HashMap<&str, String> = HashMap::new();
+    let mut leaders: HashMap<usize, String> = HashMap::new();
+    for caps in re.captures_iter(template) {
+        let (Some(group), key) = (caps.get(4), caps.get(1).unwrap().as_str()) else {
+            continue;
+        };
+        if !key.starts_with("NOUN_")
+            || !dictionary_keys.contains(key)
+            || group_names.contains_key(group.as_str())
+        {
+            continue;
+        }
+        let mut name = key.trim_start_matches("NOUN_").to_lowercase();
+        if group_names.values().any(|taken| *taken == name) {
+            name = format!("{}{}", name, group.as_str());
+        }
+        leaders.insert(caps.get(0).unwrap().start(), name.clone());
+        group_names.insert(group.as_str(), name);
+    }
+
+    re.replace_all(template, |caps: &regex::Captures| {
+        if !dictionary_keys.contains(&caps[1]) {
+            return caps[0].to_string();
+        }
+        let mut modifiers = vec![caps[1].to_string()];
+        if caps.get(2).is_some() {
+            modifiers.push("plural".to_string());
+        }
+        modifiers.extend(
+            caps[3]
+                .split(':')
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string),
+        );
+        if let Some(name) = leaders.get(&caps.get(0).unwrap().start()) {
+            modifiers.push(format!("as={}", name));
+        } else if let Some(name) = caps
+            .get(4)
+            .and_then(|group| group_names.get(group.as_str()))
+        {
+            modifiers.push(format!("agree={}", name));
+        }
+        format!("{{{}}}", modifiers.join("|"))
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_modifiers() {
+        let template = Template::parse(
+            "The {ADJ_TASTE|agree=veg|a|capitalize} {NOUN_VEGETABLE|plural|gen|as=veg}, {veg}",
+        )
+        .unwrap();
+        assert!(
+            matches!(&template.segments()[0], Segment::Literal(range) if template.text(range) == "The ")
+        );
+        let placeholders: Vec<&Placeholder> = template.slots().collect();
+        assert_eq!(placeholders.len(), 3);
+        assert_eq!(placeholders[0].agree.as_deref(), Some("veg"));
+        assert!(placeholders[0].article && placeholders[0].capitalize);
+        assert_eq!(
+            placeholders[1].source,
+            Source::Dictionary("NOUN_VEGETABLE".to_string())
+        );
+        assert!(placeholders[1].plural);
+        assert_eq!(placeholders[1].case, Some(Case::Genitive));
+        assert_eq!(placeholders[2].source, Source::Reference("veg".to_string()));
+        assert_eq!(
+            template_dictionary_keys("NATO {NOUN_WEAPON} since {YEAR}"),
+            ["NOUN_WEAPON", "YEAR"]
+        );
+
+        let template = Template::parse("{NOUN_WEAPON[explosive, rare]|plural}").unwrap();
+        let slot = template.slots().next().unwrap();
+        assert_eq!(slot.dictionary_key(), Some("NOUN_WEAPON"));
+        assert_eq!(slot.tags, ["explosive", "rare"]);
+        assert!(template_syntax_error("{NOUN_WEAPON[Explosive]}").is_some());
+        assert!(template_syntax_error("{NOUN_WEAPON[]}").is_some());
+
+        let template = Template::parse("{$victim|capitalize} met {$weapon|a}").unwrap();
+        assert_eq!(
+            template.slots().next().unwrap().source,
+            Source::Context("victim".to_string())
+        );
+        assert!(template_syntax_error("{$victim|plural}").is_some());
+        assert!(template_syntax_error("{NOUN_WEAPON|shiny}").is_some());
+        assert!(template_syntax_error("{ADJ_TASTE|agree=veg}").is_some());
+    }
+
+    #[test]
+    fn migrates_legacy_templates() {
+        let keys: BTreeSet<String> = [
+            "ADJ_TASTE",
+            "ADJ_QUALITY",
+            "ADJ_BATTLE",
+            "ADJ_STATE",
+            "NOUN_VEGETABLE",
+            "YEAR",
+        ]
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+        assert_eq!(
+            migrate_legacy_template(
+                "ADJ_TASTE#1 NOUN_VEGETABLE_PLURAL#1 dla ADJ_TASTE:gen:f wojny",
+                &keys
+            ),
+            "{ADJ_TASTE|agree=vegetable} {NOUN_VEGETABLE|plural|as=vegetable} dla {ADJ_TASTE|gen|f} wojny"
+        );
+        assert_eq!(
+            migrate_legacy_template(
+                "NOUN_VEGETABLE#1 ADJ_QUALITY#1, ADJ_BATTLE#2 NOUN_VEGETABLE#2",
+                &keys
+            ),
+            "{NOUN_VEGETABLE|as=vegetable} {ADJ_QUALITY|agree=vegetable}, {ADJ_BATTLE|agree=vegetable2} {NOUN_VEGETABLE|as=vegetable2}"
+        );
+        let migrated =
+            migrate_legacy_template("NOUN_VEGETABLE_PLURAL: ADJ_STATE since YEAR", &keys);
+        assert_eq!(
+            migrated,
+            "{NOUN_VEGETABLE|plural}: {ADJ_STATE} since {YEAR}"
+        );
+        assert_eq!(migrate_legacy_template(&migrated, &keys), migrated);
+    }
+
+    #[test]
+    fn legacy_migration_keeps_acronyms() {
+        let keys: BTreeSet<String> = ["NOUN_VEGETABLE".to_string()].into();
+        assert_eq!(
+            migrate_legacy_template("NATO bans the UFO_PLURAL of NOUN_VEGETABLE", &keys),
+            "NATO bans the UFO_PLURAL of {NOUN_VEGETABLE}"
+        );
+    }
+}
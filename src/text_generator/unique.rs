@@ -0,0 +1,161 @@
+use rand::Rng;
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+
+use super::{WhackaMoleeGenerator, category};
+
+/// How many names of the session the batch methods steer clear of.
+const RECENT_NAMES_REMEMBERED: usize = 64;
+/// Draws allowed per requested name before a batch gives up; enough to find
+/// the last free names of a small template space, cheap when there are none.
+const DRAWS_PER_NAME: usize = 50;
+/// Largest batch drawn; larger ones fail straight away, before the draw count
+/// or the name buffer can overflow.
+const MAX_UNIQUE_NAMES: usize = 10_000;
+
+/// Names handed out by the batch methods, oldest first.
+#[derive(Debug, Clone, Default)]
+pub(super) struct RecentNames(VecDeque<String>);
+
+impl RecentNames {
+    fn contains(&self, name: &str) -> bool {
+        self.0.iter().any(|recent| recent == name)
+    }
+
+    fn remember(&mut self, name: &str) {
+        if self.0.len() == RECENT_NAMES_REMEMBERED {
+            self.0.pop_front();
+        }
+        self.0.push_back(name.to_string());
+    }
+}
+
+/// A batch asked for more distinct names than the templates and dictionaries
+/// could produce. `names` holds the distinct ones that were found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotEnoughUniqueNames {
+    pub requested: usize,
+    pub names: Vec<String>,
+}
+
+impl fmt::Display for NotEnoughUniqueNames {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "only {} distinct names of the {} requested could be generated",
+            self.names.len(),
+            self.requested
+        )
+    }
+}
+
+impl std::error::Error for NotEnoughUniqueNames {}
+
+impl WhackaMoleeGenerator {
+    /// `n` distinct team names, e.g. one per team of a match. Names handed out
+    /// by earlier batches are avoided while others can be found, and names
+    /// keep to the category's `MAX_LENGTHS` limit.
+    pub fn generate_unique_team_names(
+        &mut self,
+        n: usize,
+    ) -> Result<Vec<String>, NotEnoughUniqueNames> {
+        self.generate_unique_team_names_with_rng(n, &mut rand::rng())
+    }
+
+    /// `n` distinct terrain names, see [`Self::generate_unique_team_names`].
+    pub fn generate_unique_terrain_names(
+        &mut self,
+        n: usize,
+    ) -> Result<Vec<String>, NotEnoughUniqueNames> {
+        self.generate_unique_terrain_names_with_rng(n, &mut rand::rng())
+    }
+
+    /// `n` distinct texts of `category`, see [`Self::generate_unique_team_names`].
+    pub fn generate_unique(
+        &mut self,
+        category: &str,
+        n: usize,
+    ) -> Result<Vec<String>, NotEnoughUniqueNames> {
+        self.generate_unique_with_rng(category, n, &mut rand::rng())
+    }
+
+    /// Like [`Self::generate_unique`], drawing every choice from `rng`.
+    pub fn generate_unique_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        category: &str,
+        n: usize,
+        rng: &mut R,
+    ) -> Result<Vec<String>, NotEnoughUniqueNames> {
+        self.collect_unique(n, rng, |generator, rng| {
+            generator.generate_fitting_with_rng(category, rng).ok()
+        })
+    }
+
+    /// Like [`Self::generate_unique_team_names`], drawing every choice from `rng`.
+    pub fn generate_unique_team_names_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        n: usize,
+        rng: &mut R,
+    ) -> Result<Vec<String>, NotEnoughUniqueNames> {
+        self.generate_unique_with_rng(category::TEAM_NAME, n, rng)
+    }
+
+    /// Like [`Self::generate_unique_terrain_names`], drawing every choice from `rng`.
+    pub fn generate_unique_terrain_names_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        n: usize,
+        rng: &mut R,
+    ) -> Result<Vec<String>, NotEnoughUniqueNames> {
+        self.generate_unique_with_rng(category::TERRAIN_NAME, n, rng)
+    }
+
+    /// Draws until `n` distinct names are found. Recent names are set aside and
+    /// only used when the draws run out, since a repeat from an earlier match
+    /// beats failing the batch. More than [`MAX_UNIQUE_NAMES`] fail without a draw.
+    fn collect_unique<R: Rng + ?Sized>(
+        &mut self,
+        n: usize,
+        rng: &mut R,
+        generate: impl Fn(&Self, &mut R) -> Option<String>,
+    ) -> Result<Vec<String>, NotEnoughUniqueNames> {
+        if n > MAX_UNIQUE_NAMES {
+            return Err(NotEnoughUniqueNames {
+                requested: n,
+                names: Vec::new(),
+            });
+        }
+        let mut names = Vec::with_capacity(n);
+        let mut seen = HashSet::new();
+        let mut recent = Vec::new();
+        for _ in 0..n * DRAWS_PER_NAME {
+            if names.len() == n {
+                break;
+            }
+            let Some(name) = generate(self, rng) else {
+                continue;
+            };
+            if !seen.insert(name.clone()) {
+                continue;
+            }
+            if self.recent_names.contains(&name) {
+                recent.push(name);
+            } else {
+                names.push(name);
+            }
+        }
+        let missing = n - names.len();
+        names.extend(recent.into_iter().take(missing));
+
+        for name in &names {
+            self.recent_names.remember(name);
+        }
+        if names.len() < n {
+            Err(NotEnoughUniqueNames {
+                requested: n,
+                names,
+            })
+        } else {
+            Ok(names)
+        }
+    }
+}
@@ -0,0 +1,363 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use super::template::{Source, Template};
+use super::{DictionaryEntry, TextContext, WhackaMoleeGenerator};
+
+/// How bad a [`ValidationIssue`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The data works, but something in it is likely a mistake.
+    Warning,
+    /// Generated text would show raw placeholders or error strings.
+    Error,
+}
+
+/// Something found while checking a language's templates and dictionaries.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ValidationIssue {
+    /// A category with an empty template list, which generates an `ERR_NO_...` string.
+    EmptyTemplateCategory { category: String },
+    /// A template refers to a dictionary that does not exist.
+    UnknownDictionaryKey {
+        category: String,
+        template: String,
+        key: String,
+    },
+    /// A template refers to a dictionary with no words in it, or only words of
+    /// weight 0.
+    EmptyDictionary { key: String },
+    /// A template asks for tags no word of the dictionary carries, e.g.
+    /// `{NOUN_WEAPON[explosive]}` without any explosive weapon.
+    NoTaggedWords { key: String, tags: Vec<String> },
+    /// A word's weight is negative or not a number.
+    InvalidWeight { key: String, word: String },
+    /// A dictionary word contains a placeholder. Words are never expanded, so
+    /// it would show as it is; a word naming its own dictionary could not be
+    /// expanded at all.
+    PlaceholderInWord { key: String, word: String },
+    /// No template refers to this dictionary.
+    UnusedDictionary { key: String },
+    /// The longest text the template can produce is over its category's budget.
+    TemplateTooLong {
+        category: String,
+        template: String,
+        max_length: usize,
+        budget: usize,
+    },
+    /// Even the shortest text the template can produce is over the category's
+    /// `MAX_LENGTHS` limit, so limited generation never uses it.
+    TemplateCannotFit {
+        category: String,
+        template: String,
+        min_length: usize,
+        limit: usize,
+    },
+    /// No template of the category fits its `MAX_LENGTHS` limit.
+    NoTemplateFits { category: String, limit: usize },
+}
+
+impl ValidationIssue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::UnusedDictionary { .. }
+            | Self::TemplateTooLong { .. }
+            | Self::TemplateCannotFit { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyTemplateCategory { category } => write!(f, "{} has no templates", category),
+            Self::UnknownDictionaryKey {
+                category,
+                template,
+                key,
+            } => {
+                write!(
+                    f,
+                    "{} template '{}' uses unknown dictionary {}",
+                    category, template, key
+                )
+            }
+            Self::EmptyDictionary { key } => write!(f, "dictionary {} is empty", key),
+            Self::NoTaggedWords { key, tags } => {
+                write!(
+                    f,
+                    "dictionary {} has no word tagged [{}]",
+                    key,
+                    tags.join(",")
+                )
+            }
+            Self::InvalidWeight { key, word } => write!(
+                f,
+                "dictionary {} word '{}' has an invalid weight",
+                key, word
+            ),
+            Self::PlaceholderInWord { key, word } => {
+                write!(
+                    f,
+                    "dictionary {} word '{}' contains a placeholder",
+                    key, word
+                )
+            }
+            Self::UnusedDictionary { key } => {
+                write!(f, "dictionary {} is not used by any template", key)
+            }
+            Self::TemplateTooLong {
+                category,
+                template,
+                max_length,
+                budget,
+            } => write!(
+                f,
+                "{} template '{}' can produce {} characters, over the budget of {}",
+                category, template, max_length, budget
+            ),
+            Self::TemplateCannotFit {
+                category,
+                template,
+                min_length,
+                limit,
+            } => write!(
+                f,
+                "{} template '{}' produces at least {} characters, over the limit of {}",
+                category, template, min_length, limit
+            ),
+            Self::NoTemplateFits { category, limit } => {
+                write!(
+                    f,
+                    "no {} template fits the limit of {} characters",
+                    category, limit
+                )
+            }
+        }
+    }
+}
+
+/// Everything found while checking a language's templates and dictionaries.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub lang: String,
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity() == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity() == Severity::Warning)
+    }
+
+    /// Whether the data can generate text without errors showing on screen.
+    pub fn is_usable(&self) -> bool {
+        self.errors().next().is_none()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "'{}' text generator data has {} errors",
+            self.lang,
+            self.errors().count()
+        )?;
+        for issue in self.errors() {
+            write!(f, "\n  {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// Length assumed for a `{$NAME}` value, about that of a mole or weapon name.
+const CONTEXT_VALUE_LENGTH: usize = 16;
+
+/// Longest text, in characters, each category without a `MAX_LENGTHS` limit
+/// should produce: names are shorter than sentences, and commentary wraps in
+/// the ticker.
+fn length_budget(category: &str) -> usize {
+    if category.ends_with("_NAME") {
+        64
+    } else if category.starts_with("COMMENTARY_") {
+        120
+    } else {
+        80
+    }
+}
+
+impl WhackaMoleeGenerator {
+    pub(super) fn validate(&self, lang: &str) -> ValidationReport {
+        let mut issues = BTreeSet::new();
+        let mut used = BTreeSet::new();
+        for (category, templates) in self.templates.categories() {
+            if templates.is_empty() {
+                issues.insert(ValidationIssue::EmptyTemplateCategory {
+                    category: category.to_string(),
+                });
+            }
+            for template in templates {
+                for slot in template.slots() {
+                    let Some(key) = slot.dictionary_key() else {
+                        continue;
+                    };
+                    used.insert(key);
+                    let Some(mut entries) = self.entries_tagged(key, &slot.tags) else {
+                        issues.insert(ValidationIssue::UnknownDictionaryKey {
+                            category: category.to_string(),
+                            template: template.source().to_string(),
+                            key: key.to_string(),
+                        });
+                        continue;
+                    };
+                    if entries.any(|entry| entry.weight() > 0.0) {
+                        continue;
+                    }
+                    issues.insert(match slot.tags.is_empty() {
+                        true => ValidationIssue::EmptyDictionary {
+                            key: key.to_string(),
+                        },
+                        false => ValidationIssue::NoTaggedWords {
+                            key: key.to_string(),
+                            tags: slot.tags.clone(),
+                        },
+                    });
+                }
+                let Some(lengths) = self.length_range(template, None) else {
+                    continue;
+                };
+                match self.templates.max_length(category) {
+                    Some(limit) if *lengths.start() > limit => {
+                        issues.insert(ValidationIssue::TemplateCannotFit {
+                            category: category.to_string(),
+                            template: template.source().to_string(),
+                            min_length: *lengths.start(),
+                            limit,
+                        });
+                    }
+                    Some(_) => {}
+                    None if *lengths.end() > length_budget(category) => {
+                        issues.insert(ValidationIssue::TemplateTooLong {
+                            category: category.to_string(),
+                            template: template.source().to_string(),
+                            max_length: *lengths.end(),
+                            budget: length_budget(category),
+                        });
+                    }
+                    None => {}
+                }
+            }
+            if let Some(limit) = self.templates.max_length(category)
+                && !templates.is_empty()
+                && templates.iter().all(|template| {
+                    self.length_range(template, None)
+                        .is_some_and(|lengths| *lengths.start() > limit)
+                })
+            {
+                issues.insert(ValidationIssue::NoTemplateFits {
+                    category: category.to_string(),
+                    limit,
+                });
+            }
+        }
+
+        for (key, entries) in &self.dictionaries.dictionaries {
+            if !used.contains(key.as_str()) {
+                issues.insert(ValidationIssue::UnusedDictionary { key: key.clone() });
+            }
+            for entry in entries {
+                if !(entry.weight() >= 0.0 && entry.weight().is_finite()) {
+                    issues.insert(ValidationIssue::InvalidWeight {
+                        key: key.clone(),
+                        word: entry.word().to_string(),
+                    });
+                }
+                if Template::parse(entry.word())
+                    .is_ok_and(|template| template.slots().next().is_some())
+                {
+                    issues.insert(ValidationIssue::PlaceholderInWord {
+                        key: key.clone(),
+                        word: entry.word().to_string(),
+                    });
+                }
+            }
+        }
+
+        ValidationReport {
+            lang: lang.to_string(),
+            issues: issues.into_iter().collect(),
+        }
+    }
+
+    /// Bounds of the length of `template`'s text: every slot filled with the
+    /// shortest word of its dictionary, or the longest form of the longest.
+    /// Context values count as long as they are in `context`, or as
+    /// [`CONTEXT_VALUE_LENGTH`] without it. `None` when a dictionary is
+    /// missing, which is reported on its own.
+    pub(super) fn length_range(
+        &self,
+        template: &Template,
+        context: Option<&TextContext>,
+    ) -> Option<RangeInclusive<usize>> {
+        let (mut shortest, mut longest) = (template.literal_length(), template.literal_length());
+        for slot in template.slots() {
+            let key = match &slot.source {
+                Source::Dictionary(key) => key.as_str(),
+                Source::Context(name) => {
+                    let length = context
+                        .and_then(|context| context.get(name))
+                        .map_or(CONTEXT_VALUE_LENGTH, |value| {
+                            self.render_context_value(slot, value).chars().count()
+                        });
+                    shortest += length;
+                    longest += length;
+                    continue;
+                }
+                Source::Reference(name) => template
+                    .slots()
+                    .find(|named| named.name.as_ref() == Some(name))?
+                    .dictionary_key()?,
+            };
+            let (slot_shortest, slot_longest) = self
+                .entries_tagged(key, &slot.tags)?
+                .map(|entry| {
+                    let rendered = self.render(slot, key, entry, None, false).chars().count();
+                    (rendered, rendered.max(longest_form(entry)))
+                })
+                .reduce(|(a_short, a_long), (b_short, b_long)| {
+                    (a_short.min(b_short), a_long.max(b_long))
+                })
+                .unwrap_or((0, 0));
+            shortest += slot_shortest;
+            longest += slot_longest;
+        }
+        Some(shortest..=longest)
+    }
+}
+
+fn longest_form(entry: &DictionaryEntry) -> usize {
+    match entry {
+        DictionaryEntry::Word(word) => word.chars().count(),
+        DictionaryEntry::Forms {
+            word,
+            plural,
+            forms,
+            ..
+        } => std::iter::once(word)
+            .chain(plural)
+            .chain(forms.values())
+            .map(|form| form.chars().count())
+            .max()
+            .unwrap_or(0),
+    }
+}
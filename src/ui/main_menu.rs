@@ -1,10 +1,11 @@
 // src/ui/main_menu.rs
-// version:0.0.1
+// version:0.0.2
 // ----START OF FILE----
 use bevy::app::AppExit;
 use bevy::prelude::*;
 
 use crate::game_states::AppState;
+use crate::localization::{LocalizedText, TextRole};
 
 pub struct MainMenuPlugin;
 
@@ -30,14 +31,14 @@ enum MainMenuButtonAction {
     Quit,
 }
 
-const NORMAL_BUTTON_BG_COLOR: Color = Color::rgb(0.25, 0.25, 0.25);
-const HOVERED_BUTTON_BG_COLOR: Color = Color::rgb(0.35, 0.35, 0.35);
-const PRESSED_BUTTON_BG_COLOR: Color = Color::rgb(0.15, 0.15, 0.15);
-const BUTTON_TEXT_COLOR: Color = Color::WHITE;
-const BUTTON_BORDER_COLOR: Color = Color::BLACK;
-const BUTTON_HOVERED_BORDER_COLOR: Color = Color::WHITE;
+pub(super) const NORMAL_BUTTON_BG_COLOR: Color = Color::rgb(0.25, 0.25, 0.25);
+pub(super) const HOVERED_BUTTON_BG_COLOR: Color = Color::rgb(0.35, 0.35, 0.35);
+pub(super) const PRESSED_BUTTON_BG_COLOR: Color = Color::rgb(0.15, 0.15, 0.15);
+pub(super) const BUTTON_TEXT_COLOR: Color = Color::WHITE;
+pub(super) const BUTTON_BORDER_COLOR: Color = Color::BLACK;
+pub(super) const BUTTON_HOVERED_BORDER_COLOR: Color = Color::WHITE;
 
-fn get_button_style() -> Style {
+pub(super) fn get_button_style() -> Style {
     Style {
         width: Val::Px(250.0),
         height: Val::Px(65.0),
@@ -49,7 +50,7 @@ fn get_button_style() -> Style {
     }
 }
 
-fn get_button_text_style(_asset_server: &AssetServer) -> TextStyle {
+pub(super) fn get_button_text_style(_asset_server: &AssetServer) -> TextStyle {
     TextStyle {
         font_size: 30.0,
         color: BUTTON_TEXT_COLOR,
@@ -65,8 +66,8 @@ fn setup_main_menu_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                     width: Val::Percent(100.0),
                     height: Val::Percent(100.0),
                     justify_content: JustifyContent::FlexStart, // Align container to top
-                    align_items: AlignItems::FlexEnd,      // Align container to right
-                    flex_direction: FlexDirection::Column, // Main axis for screen
+                    align_items: AlignItems::FlexEnd,           // Align container to right
+                    flex_direction: FlexDirection::Column,      // Main axis for screen
                     ..default()
                 },
                 ..default()
@@ -88,19 +89,19 @@ fn setup_main_menu_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                 .with_children(|button_container| {
                     spawn_main_menu_button(
                         button_container,
-                        "Start Game",
+                        "main-menu-start-game",
                         MainMenuButtonAction::StartGame,
                         &asset_server,
                     );
                     spawn_main_menu_button(
                         button_container,
-                        "Options",
+                        "main-menu-options",
                         MainMenuButtonAction::Options,
                         &asset_server,
                     );
                     spawn_main_menu_button(
                         button_container,
-                        "Quit",
+                        "main-menu-quit-game",
                         MainMenuButtonAction::Quit,
                         &asset_server,
                     );
@@ -110,7 +111,7 @@ fn setup_main_menu_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
 
 fn spawn_main_menu_button(
     parent: &mut ChildBuilder,
-    text: &str,
+    text_key: &str,
     action: MainMenuButtonAction,
     asset_server: &Res<AssetServer>,
 ) {
@@ -125,17 +126,23 @@ fn spawn_main_menu_button(
             action,
         ))
         .with_children(|btn_parent| {
-            btn_parent.spawn(TextBundle::from_section(
-                text,
-                get_button_text_style(asset_server),
+            btn_parent.spawn((
+                TextBundle::from_section("", get_button_text_style(asset_server)),
+                LocalizedText::new(text_key),
+                TextRole::Button,
             ));
         });
 }
 
+#[allow(clippy::type_complexity)]
 fn button_interaction_visuals(
     mut interaction_query: Query<
         (&Interaction, &mut BackgroundColor, &mut BorderColor),
-        (Changed<Interaction>, With<Button>, With<MainMenuButtonAction>),
+        (
+            Changed<Interaction>,
+            With<Button>,
+            With<MainMenuButtonAction>,
+        ),
     >,
 ) {
     for (interaction, mut bg_color, mut border_color) in &mut interaction_query {
@@ -156,8 +163,12 @@ fn button_interaction_visuals(
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn main_menu_action_system(
-    interaction_query: Query<(&Interaction, &MainMenuButtonAction), (Changed<Interaction>, With<Button>)>,
+    interaction_query: Query<
+        (&Interaction, &MainMenuButtonAction),
+        (Changed<Interaction>, With<Button>),
+    >,
     mut app_state_next: ResMut<NextState<AppState>>,
     mut app_exit_events: EventWriter<AppExit>,
 ) {
@@ -185,4 +196,4 @@ fn cleanup_main_menu_ui(mut commands: Commands, query: Query<Entity, With<MainMe
 }
 // ----END OF FILE----
 // src/ui/main_menu.rs
-// version:0.0.1
\ No newline at end of file
+// version:0.0.2
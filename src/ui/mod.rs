@@ -1,7 +1,8 @@
 // src/ui/mod.rs
-// version:0.0.1
+// version:0.0.2
 // ----START OF FILE----
 pub mod main_menu;
+pub mod options_menu;
 // ----END OF FILE----
 // src/ui/mod.rs
-// version:0.0.1
\ No newline at end of file
+// version:0.0.2
@@ -0,0 +1,220 @@
+// src/ui/options_menu.rs
+// version:0.0.1
+// ----START OF FILE----
+use bevy::prelude::*;
+
+use super::main_menu::{
+    BUTTON_BORDER_COLOR, BUTTON_HOVERED_BORDER_COLOR, BUTTON_TEXT_COLOR, HOVERED_BUTTON_BG_COLOR,
+    NORMAL_BUTTON_BG_COLOR, PRESSED_BUTTON_BG_COLOR, get_button_style, get_button_text_style,
+};
+use crate::game_states::AppState;
+use crate::localization::{
+    AvailableLanguages, CurrentLang, LanguageChangeRequest, LocalizedText, TextRole,
+};
+
+pub struct OptionsMenuPlugin;
+
+impl Plugin for OptionsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::OptionsMenu), setup_options_menu_ui)
+            .add_systems(
+                Update,
+                (
+                    options_menu_action_system,
+                    button_interaction_visuals,
+                    highlight_current_language,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::OptionsMenu)),
+            )
+            .add_systems(OnExit(AppState::OptionsMenu), cleanup_options_menu_ui);
+    }
+}
+
+#[derive(Component)]
+struct OptionsMenuUITag;
+
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+enum OptionsButtonAction {
+    SelectLanguage(String),
+    Back,
+}
+
+fn setup_options_menu_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    available: Res<AvailableLanguages>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            OptionsMenuUITag,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 50.0,
+                        color: BUTTON_TEXT_COLOR,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(30.0)),
+                    ..default()
+                }),
+                LocalizedText::new("options-title"),
+                TextRole::Title,
+            ));
+            parent.spawn((
+                TextBundle::from_section("", get_button_text_style(&asset_server)).with_style(
+                    Style {
+                        margin: UiRect::bottom(Val::Px(10.0)),
+                        ..default()
+                    },
+                ),
+                LocalizedText::new("options-language-select"),
+                TextRole::Body,
+            ));
+
+            // Languages are listed under their own name, so they are not localized.
+            for language in available.iter() {
+                spawn_options_button(
+                    parent,
+                    Text::from_section(
+                        language.display_name.clone(),
+                        get_button_text_style(&asset_server),
+                    ),
+                    None,
+                    OptionsButtonAction::SelectLanguage(language.code.clone()),
+                );
+            }
+            spawn_options_button(
+                parent,
+                Text::from_section("", get_button_text_style(&asset_server)),
+                Some(LocalizedText::new("options-back-button")),
+                OptionsButtonAction::Back,
+            );
+        });
+}
+
+fn spawn_options_button(
+    parent: &mut ChildBuilder,
+    text: Text,
+    localized: Option<LocalizedText>,
+    action: OptionsButtonAction,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: get_button_style(),
+                border_color: BorderColor(BUTTON_BORDER_COLOR),
+                background_color: NORMAL_BUTTON_BG_COLOR.into(),
+                ..default()
+            },
+            action,
+        ))
+        .with_children(|btn_parent| {
+            let mut label = btn_parent.spawn((TextBundle { text, ..default() }, TextRole::Button));
+            if let Some(localized) = localized {
+                label.insert(localized);
+            }
+        });
+}
+
+fn is_current_language(action: &OptionsButtonAction, current_lang: &CurrentLang) -> bool {
+    matches!(action, OptionsButtonAction::SelectLanguage(code) if *code == current_lang.0)
+}
+
+fn idle_border_color(action: &OptionsButtonAction, current_lang: &CurrentLang) -> Color {
+    if is_current_language(action, current_lang) {
+        BUTTON_HOVERED_BORDER_COLOR
+    } else {
+        BUTTON_BORDER_COLOR
+    }
+}
+
+fn button_interaction_visuals(
+    mut interaction_query: Query<
+        (
+            &Interaction,
+            &OptionsButtonAction,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        Changed<Interaction>,
+    >,
+    current_lang: Res<CurrentLang>,
+) {
+    for (interaction, action, mut bg_color, mut border_color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *bg_color = PRESSED_BUTTON_BG_COLOR.into();
+                *border_color = BUTTON_HOVERED_BORDER_COLOR.into();
+            }
+            Interaction::Hovered => {
+                *bg_color = HOVERED_BUTTON_BG_COLOR.into();
+                *border_color = BUTTON_HOVERED_BORDER_COLOR.into();
+            }
+            Interaction::None => {
+                *bg_color = NORMAL_BUTTON_BG_COLOR.into();
+                *border_color = idle_border_color(action, &current_lang).into();
+            }
+        }
+    }
+}
+
+/// Outlines the button of the language in use, including right after entering the screen.
+fn highlight_current_language(
+    mut button_query: Query<(&Interaction, &OptionsButtonAction, &mut BorderColor)>,
+    current_lang: Res<CurrentLang>,
+    added: Query<(), Added<OptionsButtonAction>>,
+) {
+    if !current_lang.is_changed() && added.is_empty() {
+        return;
+    }
+    for (interaction, action, mut border_color) in &mut button_query {
+        if *interaction == Interaction::None {
+            *border_color = idle_border_color(action, &current_lang).into();
+        }
+    }
+}
+
+fn options_menu_action_system(
+    interaction_query: Query<(&Interaction, &OptionsButtonAction), Changed<Interaction>>,
+    mut app_state_next: ResMut<NextState<AppState>>,
+    mut language_change_events: EventWriter<LanguageChangeRequest>,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match action {
+                OptionsButtonAction::SelectLanguage(code) => {
+                    language_change_events.send(LanguageChangeRequest(code.clone()));
+                }
+                OptionsButtonAction::Back => {
+                    app_state_next.set(AppState::MainMenu);
+                }
+            }
+        }
+    }
+}
+
+fn cleanup_options_menu_ui(mut commands: Commands, query: Query<Entity, With<OptionsMenuUITag>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+// ----END OF FILE----
+// src/ui/options_menu.rs
+// version:0.0.1